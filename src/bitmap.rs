@@ -0,0 +1,416 @@
+/// The order in which the rows of a bitmap are laid out in memory. Pixel coordinates always
+/// have `y = 0` at the bottom row, regardless of the row order.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RowOrder {
+    BottomUp,
+    TopDown,
+}
+
+impl Default for RowOrder {
+    #[inline]
+    fn default() -> Self {
+        Self::BottomUp
+    }
+}
+
+#[inline]
+fn pixel_offset<const N: usize>(
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    row_stride: usize,
+    row_order: RowOrder,
+) -> usize {
+    assert!(x < width && y < height, "pixel out of bounds");
+    let row = match row_order {
+        RowOrder::BottomUp => y,
+        RowOrder::TopDown => height - 1 - y,
+    };
+    row * row_stride + x * N
+}
+
+#[inline]
+fn check_layout<const N: usize>(len: usize, width: usize, height: usize, row_stride: usize) {
+    assert!(row_stride >= width * N, "row stride is smaller than a row");
+    if width > 0 && height > 0 {
+        assert!(
+            len >= (height - 1) * row_stride + width * N,
+            "pixel buffer is too small"
+        );
+    }
+}
+
+/// An owned bitmap of `N`-channel pixels.
+#[derive(Clone)]
+pub struct Bitmap<T, const N: usize> {
+    pixels: Vec<T>,
+    width: usize,
+    height: usize,
+    row_order: RowOrder,
+}
+
+impl<T: Default + Clone, const N: usize> Bitmap<T, N> {
+    #[inline]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_row_order(width, height, RowOrder::BottomUp)
+    }
+
+    #[inline]
+    pub fn with_row_order(width: usize, height: usize, row_order: RowOrder) -> Self {
+        Self {
+            pixels: vec![T::default(); width * height * N],
+            width,
+            height,
+            row_order,
+        }
+    }
+}
+
+impl<T, const N: usize> Bitmap<T, N> {
+    pub fn from_pixels(pixels: Vec<T>, width: usize, height: usize, row_order: RowOrder) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height * N,
+            "pixel buffer size mismatch"
+        );
+        Self {
+            pixels,
+            width,
+            height,
+            row_order,
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn row_order(&self) -> RowOrder {
+        self.row_order
+    }
+
+    #[inline]
+    pub fn pixels(&self) -> &[T] {
+        &self.pixels
+    }
+
+    #[inline]
+    pub fn pixels_mut(&mut self) -> &mut [T] {
+        &mut self.pixels
+    }
+
+    #[inline]
+    pub fn into_pixels(self) -> Vec<T> {
+        self.pixels
+    }
+
+    #[inline]
+    pub fn pixel(&self, x: usize, y: usize) -> &[T; N] {
+        let i = pixel_offset::<N>(
+            x,
+            y,
+            self.width,
+            self.height,
+            self.width * N,
+            self.row_order,
+        );
+        self.pixels[i..i + N].try_into().unwrap()
+    }
+
+    #[inline]
+    pub fn pixel_mut(&mut self, x: usize, y: usize) -> &mut [T; N] {
+        let i = pixel_offset::<N>(
+            x,
+            y,
+            self.width,
+            self.height,
+            self.width * N,
+            self.row_order,
+        );
+        (&mut self.pixels[i..i + N]).try_into().unwrap()
+    }
+
+    #[inline]
+    pub fn as_ref(&self) -> BitmapRef<'_, T, N> {
+        BitmapRef {
+            pixels: &self.pixels,
+            width: self.width,
+            height: self.height,
+            row_stride: self.width * N,
+            row_order: self.row_order,
+        }
+    }
+
+    #[inline]
+    pub fn as_mut(&mut self) -> BitmapMut<'_, T, N> {
+        BitmapMut {
+            pixels: &mut self.pixels,
+            width: self.width,
+            height: self.height,
+            row_stride: self.width * N,
+            row_order: self.row_order,
+        }
+    }
+}
+
+/// A borrowed, read-only view of `N`-channel pixels. The row stride is measured in elements
+/// of `T`, and may be larger than `width * N` to skip padding between rows.
+pub struct BitmapRef<'a, T, const N: usize> {
+    pixels: &'a [T],
+    width: usize,
+    height: usize,
+    row_stride: usize,
+    row_order: RowOrder,
+}
+
+impl<'a, T, const N: usize> Clone for BitmapRef<'a, T, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, const N: usize> Copy for BitmapRef<'a, T, N> {}
+
+impl<'a, T, const N: usize> BitmapRef<'a, T, N> {
+    #[inline]
+    pub fn new(pixels: &'a [T], width: usize, height: usize) -> Self {
+        Self::with_layout(pixels, width, height, width * N, RowOrder::BottomUp)
+    }
+
+    pub fn with_layout(
+        pixels: &'a [T],
+        width: usize,
+        height: usize,
+        row_stride: usize,
+        row_order: RowOrder,
+    ) -> Self {
+        check_layout::<N>(pixels.len(), width, height, row_stride);
+        Self {
+            pixels,
+            width,
+            height,
+            row_stride,
+            row_order,
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn row_stride(&self) -> usize {
+        self.row_stride
+    }
+
+    #[inline]
+    pub fn row_order(&self) -> RowOrder {
+        self.row_order
+    }
+
+    #[inline]
+    pub fn pixel(&self, x: usize, y: usize) -> &'a [T; N] {
+        let i = pixel_offset::<N>(
+            x,
+            y,
+            self.width,
+            self.height,
+            self.row_stride,
+            self.row_order,
+        );
+        self.pixels[i..i + N].try_into().unwrap()
+    }
+}
+
+/// A borrowed, writable view of `N`-channel pixels. See [`BitmapRef`].
+pub struct BitmapMut<'a, T, const N: usize> {
+    pixels: &'a mut [T],
+    width: usize,
+    height: usize,
+    row_stride: usize,
+    row_order: RowOrder,
+}
+
+impl<'a, T, const N: usize> BitmapMut<'a, T, N> {
+    #[inline]
+    pub fn new(pixels: &'a mut [T], width: usize, height: usize) -> Self {
+        Self::with_layout(pixels, width, height, width * N, RowOrder::BottomUp)
+    }
+
+    pub fn with_layout(
+        pixels: &'a mut [T],
+        width: usize,
+        height: usize,
+        row_stride: usize,
+        row_order: RowOrder,
+    ) -> Self {
+        check_layout::<N>(pixels.len(), width, height, row_stride);
+        Self {
+            pixels,
+            width,
+            height,
+            row_stride,
+            row_order,
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn row_stride(&self) -> usize {
+        self.row_stride
+    }
+
+    #[inline]
+    pub fn row_order(&self) -> RowOrder {
+        self.row_order
+    }
+
+    #[inline]
+    pub fn pixel(&self, x: usize, y: usize) -> &[T; N] {
+        let i = pixel_offset::<N>(
+            x,
+            y,
+            self.width,
+            self.height,
+            self.row_stride,
+            self.row_order,
+        );
+        self.pixels[i..i + N].try_into().unwrap()
+    }
+
+    #[inline]
+    pub fn pixel_mut(&mut self, x: usize, y: usize) -> &mut [T; N] {
+        let i = pixel_offset::<N>(
+            x,
+            y,
+            self.width,
+            self.height,
+            self.row_stride,
+            self.row_order,
+        );
+        (&mut self.pixels[i..i + N]).try_into().unwrap()
+    }
+
+    #[inline]
+    pub fn as_ref(&self) -> BitmapRef<'_, T, N> {
+        BitmapRef {
+            pixels: self.pixels,
+            width: self.width,
+            height: self.height,
+            row_stride: self.row_stride,
+            row_order: self.row_order,
+        }
+    }
+
+    #[inline]
+    pub fn as_mut(&mut self) -> BitmapMut<'_, T, N> {
+        BitmapMut {
+            pixels: self.pixels,
+            width: self.width,
+            height: self.height,
+            row_stride: self.row_stride,
+            row_order: self.row_order,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> From<&'a Bitmap<T, N>> for BitmapRef<'a, T, N> {
+    #[inline]
+    fn from(bitmap: &'a Bitmap<T, N>) -> Self {
+        bitmap.as_ref()
+    }
+}
+
+impl<'a, T, const N: usize> From<&'a mut Bitmap<T, N>> for BitmapMut<'a, T, N> {
+    #[inline]
+    fn from(bitmap: &'a mut Bitmap<T, N>) -> Self {
+        bitmap.as_mut()
+    }
+}
+
+impl<'a, T, const N: usize> From<BitmapMut<'a, T, N>> for BitmapRef<'a, T, N> {
+    #[inline]
+    fn from(bitmap: BitmapMut<'a, T, N>) -> Self {
+        BitmapRef {
+            pixels: bitmap.pixels,
+            width: bitmap.width,
+            height: bitmap.height,
+            row_stride: bitmap.row_stride,
+            row_order: bitmap.row_order,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flips_top_down_rows() {
+        let bitmap = Bitmap::<u8, 1>::from_pixels(vec![0, 1, 2, 3, 4, 5], 2, 3, RowOrder::TopDown);
+        assert_eq!(bitmap.pixel(0, 0), &[4]);
+        assert_eq!(bitmap.pixel(1, 0), &[5]);
+        assert_eq!(bitmap.pixel(0, 2), &[0]);
+        let bitmap = Bitmap::<u8, 1>::from_pixels(vec![0, 1, 2, 3, 4, 5], 2, 3, RowOrder::BottomUp);
+        assert_eq!(bitmap.pixel(0, 0), &[0]);
+        assert_eq!(bitmap.pixel(0, 2), &[4]);
+    }
+
+    #[test]
+    fn skips_row_padding() {
+        // Two rows of two RGB pixels, with one element of padding after each row.
+        let mut pixels = [0u8; 13];
+        {
+            let mut bitmap =
+                BitmapMut::<u8, 3>::with_layout(&mut pixels, 2, 2, 7, RowOrder::TopDown);
+            *bitmap.pixel_mut(0, 0) = [1, 2, 3];
+            *bitmap.pixel_mut(1, 1) = [4, 5, 6];
+        }
+        assert_eq!(pixels, [0, 0, 0, 4, 5, 6, 0, 1, 2, 3, 0, 0, 0]);
+        let bitmap = BitmapRef::<u8, 3>::with_layout(&pixels, 2, 2, 7, RowOrder::BottomUp);
+        assert_eq!(bitmap.pixel(1, 0), &[4, 5, 6]);
+        assert_eq!(bitmap.pixel(0, 1), &[1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "pixel buffer is too small")]
+    fn rejects_undersized_buffers() {
+        BitmapRef::<u8, 3>::with_layout(&[0; 12], 2, 2, 7, RowOrder::BottomUp);
+    }
+
+    #[test]
+    #[should_panic(expected = "row stride is smaller than a row")]
+    fn rejects_short_row_strides() {
+        BitmapMut::<u8, 3>::with_layout(&mut [0; 12], 2, 2, 5, RowOrder::BottomUp);
+    }
+
+    #[test]
+    #[should_panic(expected = "pixel out of bounds")]
+    fn rejects_out_of_bounds_pixels() {
+        Bitmap::<u8, 1>::new(2, 2).pixel(2, 0);
+    }
+}
@@ -0,0 +1,30 @@
+use crate::{clamp_0_b, mix, BitmapRef, Vector2};
+
+/// Bilinearly interpolates the bitmap at `pos`, where pixel centers lie at half-integer
+/// coordinates. Positions outside the bitmap are clamped to the nearest edge pixel.
+pub fn interpolate<const N: usize>(bitmap: BitmapRef<f32, N>, pos: Vector2) -> [f32; N] {
+    let pos = pos - Vector2::from(0.5);
+    let l = pos.x.floor() as i64;
+    let b = pos.y.floor() as i64;
+    let lr = pos.x - l as f64;
+    let bt = pos.y - b as f64;
+    let max_x = bitmap.width() as i64 - 1;
+    let max_y = bitmap.height() as i64 - 1;
+    let r = clamp_0_b(l + 1, max_x) as usize;
+    let t = clamp_0_b(b + 1, max_y) as usize;
+    let l = clamp_0_b(l, max_x) as usize;
+    let b = clamp_0_b(b, max_y) as usize;
+    let (lb, rb, lt, rt) = (
+        bitmap.pixel(l, b),
+        bitmap.pixel(r, b),
+        bitmap.pixel(l, t),
+        bitmap.pixel(r, t),
+    );
+    let mut output = [0.0; N];
+    for i in 0..N {
+        let bottom = mix(lb[i] as f64, rb[i] as f64, lr) as f32;
+        let top = mix(lt[i] as f64, rt[i] as f64, lr) as f32;
+        output[i] = mix(bottom as f64, top as f64, bt) as f32;
+    }
+    output
+}
@@ -0,0 +1,148 @@
+use crate::{fabs, DistanceType, EdgeSegment, EdgeSelector, Shape, Vector2};
+
+pub trait ContourCombiner {
+    type Selector: EdgeSelector;
+
+    fn new(shape: &Shape) -> Self;
+    fn reset(&mut self, p: Vector2);
+    fn edge_selector(&mut self, contour_index: usize) -> &mut Self::Selector;
+    fn distance(&self, edges: &[EdgeSegment]) -> <Self::Selector as EdgeSelector>::Distance;
+}
+
+/// Simply selects the nearest contour.
+pub struct SimpleContourCombiner<S: EdgeSelector> {
+    shape_edge_selector: S,
+}
+
+impl<S: EdgeSelector> ContourCombiner for SimpleContourCombiner<S> {
+    type Selector = S;
+
+    #[inline]
+    fn new(_shape: &Shape) -> Self {
+        Self {
+            shape_edge_selector: S::default(),
+        }
+    }
+
+    #[inline]
+    fn reset(&mut self, p: Vector2) {
+        self.shape_edge_selector.reset(p);
+    }
+
+    #[inline]
+    fn edge_selector(&mut self, _contour_index: usize) -> &mut Self::Selector {
+        &mut self.shape_edge_selector
+    }
+
+    #[inline]
+    fn distance(&self, edges: &[EdgeSegment]) -> S::Distance {
+        self.shape_edge_selector.distance(edges)
+    }
+}
+
+/// Selects the nearest contour that actually forms a border between filled and unfilled area.
+pub struct OverlappingContourCombiner<S: EdgeSelector> {
+    p: Vector2,
+    windings: Vec<i32>,
+    edge_selectors: Vec<S>,
+}
+
+impl<S: EdgeSelector> ContourCombiner for OverlappingContourCombiner<S> {
+    type Selector = S;
+
+    fn new(shape: &Shape) -> Self {
+        Self {
+            p: Vector2::default(),
+            windings: shape.contours.iter().map(|c| c.winding()).collect(),
+            edge_selectors: shape.contours.iter().map(|_| S::default()).collect(),
+        }
+    }
+
+    fn reset(&mut self, p: Vector2) {
+        self.p = p;
+        for edge_selector in &mut self.edge_selectors {
+            edge_selector.reset(p);
+        }
+    }
+
+    #[inline]
+    fn edge_selector(&mut self, contour_index: usize) -> &mut Self::Selector {
+        &mut self.edge_selectors[contour_index]
+    }
+
+    fn distance(&self, edges: &[EdgeSegment]) -> S::Distance {
+        let mut shape_edge_selector = S::default();
+        let mut inner_edge_selector = S::default();
+        let mut outer_edge_selector = S::default();
+        shape_edge_selector.reset(self.p);
+        inner_edge_selector.reset(self.p);
+        outer_edge_selector.reset(self.p);
+        for (edge_selector, &winding) in self.edge_selectors.iter().zip(&self.windings) {
+            let edge_distance = edge_selector.distance(edges).resolve();
+            shape_edge_selector.merge(edge_selector);
+            if winding > 0 && edge_distance >= 0.0 {
+                inner_edge_selector.merge(edge_selector);
+            }
+            if winding < 0 && edge_distance <= 0.0 {
+                outer_edge_selector.merge(edge_selector);
+            }
+        }
+
+        let shape_distance = shape_edge_selector.distance(edges);
+        let inner_distance = inner_edge_selector.distance(edges);
+        let outer_distance = outer_edge_selector.distance(edges);
+        let inner_scalar_distance = inner_distance.resolve();
+        let outer_scalar_distance = outer_distance.resolve();
+
+        let mut distance;
+        let winding;
+        if inner_scalar_distance >= 0.0
+            && fabs(inner_scalar_distance) <= fabs(outer_scalar_distance)
+        {
+            distance = inner_distance;
+            winding = 1;
+            for (edge_selector, &w) in self.edge_selectors.iter().zip(&self.windings) {
+                if w > 0 {
+                    let contour_distance = edge_selector.distance(edges);
+                    if fabs(contour_distance.resolve()) < fabs(outer_scalar_distance)
+                        && contour_distance.resolve() > distance.resolve()
+                    {
+                        distance = contour_distance;
+                    }
+                }
+            }
+        } else if outer_scalar_distance <= 0.0
+            && fabs(outer_scalar_distance) < fabs(inner_scalar_distance)
+        {
+            distance = outer_distance;
+            winding = -1;
+            for (edge_selector, &w) in self.edge_selectors.iter().zip(&self.windings) {
+                if w < 0 {
+                    let contour_distance = edge_selector.distance(edges);
+                    if fabs(contour_distance.resolve()) < fabs(inner_scalar_distance)
+                        && contour_distance.resolve() < distance.resolve()
+                    {
+                        distance = contour_distance;
+                    }
+                }
+            }
+        } else {
+            return shape_distance;
+        }
+
+        for (edge_selector, &w) in self.edge_selectors.iter().zip(&self.windings) {
+            if w != winding {
+                let contour_distance = edge_selector.distance(edges);
+                if contour_distance.resolve() * distance.resolve() >= 0.0
+                    && fabs(contour_distance.resolve()) < fabs(distance.resolve())
+                {
+                    distance = contour_distance;
+                }
+            }
+        }
+        if distance.resolve() == shape_distance.resolve() {
+            distance = shape_distance;
+        }
+        distance
+    }
+}
@@ -0,0 +1,85 @@
+use crate::Range;
+
+/// Linear mapping of signed distances to the values stored in a distance field.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct DistanceMapping {
+    scale: f64,
+    translate: f64,
+}
+
+impl Default for DistanceMapping {
+    #[inline]
+    fn default() -> Self {
+        Self::with_scale_translate(1.0, 0.0)
+    }
+}
+
+impl From<Range> for DistanceMapping {
+    #[inline]
+    fn from(range: Range) -> Self {
+        Self::new(range)
+    }
+}
+
+impl DistanceMapping {
+    /// Maps `range.lower` to 0 and `range.upper` to 1.
+    #[inline]
+    pub fn new(range: Range) -> Self {
+        Self::with_scale_translate(1.0 / range.width(), -range.lower)
+    }
+
+    /// Maps 0 to `range.lower` and 1 to `range.upper`.
+    #[inline]
+    pub fn inverse_of(range: Range) -> Self {
+        let range_width = range.width();
+        Self::with_scale_translate(
+            range_width,
+            range.lower / if range_width != 0.0 { range_width } else { 1.0 },
+        )
+    }
+
+    #[inline]
+    const fn with_scale_translate(scale: f64, translate: f64) -> Self {
+        Self { scale, translate }
+    }
+
+    #[inline]
+    pub fn map(&self, distance: f64) -> f64 {
+        self.scale * (distance + self.translate)
+    }
+
+    /// Maps the difference between two distances.
+    #[inline]
+    pub fn map_delta(&self, delta: f64) -> f64 {
+        self.scale * delta
+    }
+
+    #[inline]
+    pub fn inverse(&self) -> Self {
+        Self::with_scale_translate(1.0 / self.scale, -self.scale * self.translate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_range_to_unit_interval() {
+        let mapping = DistanceMapping::new(Range::new(-2.0, 6.0));
+        assert_eq!(mapping.map(-2.0), 0.0);
+        assert_eq!(mapping.map(2.0), 0.5);
+        assert_eq!(mapping.map(6.0), 1.0);
+        assert_eq!(mapping.map_delta(4.0), 0.5);
+    }
+
+    #[test]
+    fn maps_unit_interval_to_range() {
+        let range = Range::new(-2.0, 6.0);
+        let mapping = DistanceMapping::inverse_of(range);
+        assert_eq!(mapping.map(0.0), -2.0);
+        assert_eq!(mapping.map(0.5), 2.0);
+        assert_eq!(mapping.map(1.0), 6.0);
+        assert_eq!(mapping, DistanceMapping::new(range).inverse());
+    }
+}
@@ -1,4 +1,6 @@
-#[derive(Copy, Clone)]
+use std::ops::{BitAnd, BitOr, BitXor};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum EdgeColor {
     Black = 0,
     Red = 1,
@@ -9,3 +11,48 @@ pub enum EdgeColor {
     Cyan = 6,
     White = 7,
 }
+
+impl EdgeColor {
+    #[inline]
+    pub const fn from_bits(bits: u8) -> Self {
+        match bits & 7 {
+            0 => Self::Black,
+            1 => Self::Red,
+            2 => Self::Green,
+            3 => Self::Yellow,
+            4 => Self::Blue,
+            5 => Self::Magenta,
+            6 => Self::Cyan,
+            _ => Self::White,
+        }
+    }
+
+    #[inline]
+    pub const fn bits(self) -> u8 {
+        self as u8
+    }
+}
+
+impl BitAnd for EdgeColor {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self::from_bits(self.bits() & rhs.bits())
+    }
+}
+
+impl BitOr for EdgeColor {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self::from_bits(self.bits() | rhs.bits())
+    }
+}
+
+impl BitXor for EdgeColor {
+    type Output = Self;
+    #[inline]
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self::from_bits(self.bits() ^ rhs.bits())
+    }
+}
@@ -0,0 +1,767 @@
+use crate::{
+    cross_product, dot_product, fabs, min, Contour, EdgeColor, EdgeHolder, EdgeSegment, Shape,
+    Vector2,
+};
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::ops::Range;
+
+const EDGE_LENGTH_PRECISION: usize = 4;
+const EDGE_DISTANCE_PRECISION: usize = 16;
+const MAX_RECOLOR_STEPS: usize = 16;
+
+#[inline]
+fn is_corner(a_dir: Vector2, b_dir: Vector2, cross_threshold: f64) -> bool {
+    dot_product(a_dir, b_dir) <= 0.0 || fabs(cross_product(a_dir, b_dir)) > cross_threshold
+}
+
+#[inline]
+fn symmetrical_trichotomy(position: usize, n: usize) -> i32 {
+    (3.0 + 2.875 * position as f64 / (n - 1) as f64 - 1.4375 + 0.5) as i32 - 3
+}
+
+fn estimate_edge_length(edge: &EdgeSegment) -> f64 {
+    let mut len = 0.0;
+    let mut prev = edge.point(0.0);
+    for i in 1..=EDGE_LENGTH_PRECISION {
+        let cur = edge.point(1.0 / EDGE_LENGTH_PRECISION as f64 * i as f64);
+        len += (cur - prev).length();
+        prev = cur;
+    }
+    len
+}
+
+#[inline]
+fn seed_extract2(seed: &mut u64) -> u8 {
+    let v = (*seed & 1) as u8;
+    *seed >>= 1;
+    v
+}
+
+#[inline]
+fn seed_extract3(seed: &mut u64) -> usize {
+    let v = (*seed % 3) as usize;
+    *seed /= 3;
+    v
+}
+
+#[inline]
+fn init_color(seed: &mut u64) -> EdgeColor {
+    const COLORS: [EdgeColor; 3] = [EdgeColor::Cyan, EdgeColor::Magenta, EdgeColor::Yellow];
+    COLORS[seed_extract3(seed)]
+}
+
+#[inline]
+fn switch_color(color: &mut EdgeColor, seed: &mut u64) {
+    let shifted = color.bits() << (1 + seed_extract2(seed));
+    *color = EdgeColor::from_bits((shifted | shifted >> 3) & EdgeColor::White.bits());
+}
+
+#[inline]
+fn switch_color_banned(color: &mut EdgeColor, seed: &mut u64, banned: EdgeColor) {
+    let combined = *color & banned;
+    if combined == EdgeColor::Red || combined == EdgeColor::Green || combined == EdgeColor::Blue {
+        *color = combined ^ EdgeColor::White;
+    } else {
+        switch_color(color, seed);
+    }
+}
+
+fn find_corners(contour: &Contour, cross_threshold: f64, corners: &mut Vec<usize>) {
+    corners.clear();
+    let mut prev_direction = contour.edges.last().unwrap().direction(1.0);
+    for (index, edge) in contour.edges.iter().enumerate() {
+        if is_corner(
+            prev_direction.normalize(),
+            edge.direction(0.0).normalize(),
+            cross_threshold,
+        ) {
+            corners.push(index);
+        }
+        prev_direction = edge.direction(1.0);
+    }
+}
+
+fn color_smooth_contour(contour: &mut Contour, color: &mut EdgeColor, seed: &mut u64) {
+    switch_color(color, seed);
+    for edge in &mut contour.edges {
+        edge.color = *color;
+    }
+}
+
+fn split_teardrop(contour: &mut Contour, corner: usize, colors: [EdgeColor; 3]) {
+    let mut parts: [Option<EdgeSegment>; 7] = Default::default();
+    let (part0, part1, part2) = contour.edges[0].split_in_thirds();
+    parts[3 * corner] = Some(part0);
+    parts[1 + 3 * corner] = Some(part1);
+    parts[2 + 3 * corner] = Some(part2);
+    let part_colors: &[EdgeColor] = if contour.edges.len() >= 2 {
+        let (part3, part4, part5) = contour.edges[1].split_in_thirds();
+        parts[3 - 3 * corner] = Some(part3);
+        parts[4 - 3 * corner] = Some(part4);
+        parts[5 - 3 * corner] = Some(part5);
+        &[
+            colors[0], colors[0], colors[1], colors[1], colors[2], colors[2],
+        ]
+    } else {
+        &colors
+    };
+    contour.clear();
+    for (part, &color) in parts.into_iter().map_while(|part| part).zip(part_colors) {
+        let mut part = part;
+        part.color = color;
+        contour.add_edge(EdgeHolder::new(part));
+    }
+}
+
+fn color_teardrop(contour: &mut Contour, corner: usize, color: &mut EdgeColor, seed: &mut u64) {
+    switch_color(color, seed);
+    let first = *color;
+    switch_color(color, seed);
+    let colors = [first, EdgeColor::White, *color];
+    let m = contour.edges.len();
+    if m >= 3 {
+        for i in 0..m {
+            contour.edges[(corner + i) % m].color =
+                colors[(1 + symmetrical_trichotomy(i, m)) as usize];
+        }
+    } else if m >= 1 {
+        // Less than three edge segments for three colors => edges must be split
+        split_teardrop(contour, corner, colors);
+    }
+}
+
+/// Assigns edge colors to the shape by splitting each contour into splines at corners sharper
+/// than `angle_threshold` (in radians). The same `seed` always yields the same coloring.
+pub fn edge_coloring_simple(shape: &mut Shape, angle_threshold: f64, seed: u64) {
+    let mut seed = seed;
+    let cross_threshold = angle_threshold.sin();
+    let mut color = init_color(&mut seed);
+    let mut corners = Vec::new();
+    for contour in &mut shape.contours {
+        if contour.edges.is_empty() {
+            continue;
+        }
+        find_corners(contour, cross_threshold, &mut corners);
+
+        if corners.is_empty() {
+            // Smooth contour
+            color_smooth_contour(contour, &mut color, &mut seed);
+        } else if corners.len() == 1 {
+            // "Teardrop" case
+            color_teardrop(contour, corners[0], &mut color, &mut seed);
+        } else {
+            // Multiple corners
+            let corner_count = corners.len();
+            let mut spline = 0;
+            let start = corners[0];
+            let m = contour.edges.len();
+            switch_color(&mut color, &mut seed);
+            let initial_color = color;
+            for i in 0..m {
+                let index = (start + i) % m;
+                if spline + 1 < corner_count && corners[spline + 1] == index {
+                    spline += 1;
+                    let banned = if spline == corner_count - 1 {
+                        initial_color
+                    } else {
+                        EdgeColor::Black
+                    };
+                    switch_color_banned(&mut color, &mut seed, banned);
+                }
+                contour.edges[index].color = color;
+            }
+        }
+    }
+}
+
+struct InkTrapCorner {
+    index: usize,
+    prev_edge_length_estimate: f64,
+    minor: bool,
+    color: EdgeColor,
+}
+
+/// Like [`edge_coloring_simple`], but corners that sit between two longer splines (such as ink
+/// traps) are treated as minor and colored so they don't consume a color switch of their own.
+pub fn edge_coloring_ink_trap(shape: &mut Shape, angle_threshold: f64, seed: u64) {
+    let mut seed = seed;
+    let cross_threshold = angle_threshold.sin();
+    let mut color = init_color(&mut seed);
+    let mut corners: Vec<InkTrapCorner> = Vec::new();
+    for contour in &mut shape.contours {
+        if contour.edges.is_empty() {
+            continue;
+        }
+        let mut spline_length = 0.0;
+        {
+            // Identify corners
+            corners.clear();
+            let mut prev_direction = contour.edges.last().unwrap().direction(1.0);
+            for (index, edge) in contour.edges.iter().enumerate() {
+                if is_corner(
+                    prev_direction.normalize(),
+                    edge.direction(0.0).normalize(),
+                    cross_threshold,
+                ) {
+                    corners.push(InkTrapCorner {
+                        index,
+                        prev_edge_length_estimate: spline_length,
+                        minor: false,
+                        color: EdgeColor::Black,
+                    });
+                    spline_length = 0.0;
+                }
+                spline_length += estimate_edge_length(edge);
+                prev_direction = edge.direction(1.0);
+            }
+        }
+
+        if corners.is_empty() {
+            // Smooth contour
+            color_smooth_contour(contour, &mut color, &mut seed);
+        } else if corners.len() == 1 {
+            // "Teardrop" case
+            color_teardrop(contour, corners[0].index, &mut color, &mut seed);
+        } else {
+            // Multiple corners
+            let corner_count = corners.len();
+            let mut major_corner_count = corner_count;
+            if corner_count > 3 {
+                corners[0].prev_edge_length_estimate += spline_length;
+                for i in 0..corner_count {
+                    if corners[i].prev_edge_length_estimate
+                        > corners[(i + 1) % corner_count].prev_edge_length_estimate
+                        && corners[(i + 1) % corner_count].prev_edge_length_estimate
+                            < corners[(i + 2) % corner_count].prev_edge_length_estimate
+                    {
+                        corners[i].minor = true;
+                        major_corner_count -= 1;
+                    }
+                }
+            }
+            let mut initial_color = EdgeColor::Black;
+            for corner in corners.iter_mut().filter(|corner| !corner.minor) {
+                major_corner_count -= 1;
+                let banned = if major_corner_count == 0 {
+                    initial_color
+                } else {
+                    EdgeColor::Black
+                };
+                switch_color_banned(&mut color, &mut seed, banned);
+                corner.color = color;
+                if initial_color == EdgeColor::Black {
+                    initial_color = color;
+                }
+            }
+            for i in 0..corner_count {
+                if corners[i].minor {
+                    let next_color = corners[(i + 1) % corner_count].color;
+                    corners[i].color = (color & next_color) ^ EdgeColor::White;
+                } else {
+                    color = corners[i].color;
+                }
+            }
+            let mut spline = 0;
+            let start = corners[0].index;
+            color = corners[0].color;
+            let m = contour.edges.len();
+            for i in 0..m {
+                let index = (start + i) % m;
+                if spline + 1 < corner_count && corners[spline + 1].index == index {
+                    spline += 1;
+                    color = corners[spline].color;
+                }
+                contour.edges[index].color = color;
+            }
+        }
+    }
+}
+
+fn edge_to_edge_distance(a: &EdgeSegment, b: &EdgeSegment, precision: usize) -> f64 {
+    if a.point(0.0) == b.point(0.0)
+        || a.point(0.0) == b.point(1.0)
+        || a.point(1.0) == b.point(0.0)
+        || a.point(1.0) == b.point(1.0)
+    {
+        return 0.0;
+    }
+    let i_fac = 1.0 / precision as f64;
+    let mut min_distance = (b.point(0.0) - a.point(0.0)).length();
+    let mut dummy = 0.0;
+    for i in 0..=precision {
+        let t = i_fac * i as f64;
+        let d = fabs(a.signed_distance(b.point(t), &mut dummy).distance);
+        min_distance = min(min_distance, d);
+    }
+    for i in 0..=precision {
+        let t = i_fac * i as f64;
+        let d = fabs(b.signed_distance(a.point(t), &mut dummy).distance);
+        min_distance = min(min_distance, d);
+    }
+    min_distance
+}
+
+fn spline_to_spline_distance(
+    shape: &Shape,
+    edge_segments: &[(usize, usize)],
+    a: Range<usize>,
+    b: Range<usize>,
+    precision: usize,
+) -> f64 {
+    let mut min_distance = f64::MAX;
+    for &(a_contour, a_edge) in &edge_segments[a] {
+        for &(b_contour, b_edge) in &edge_segments[b.clone()] {
+            if min_distance == 0.0 {
+                break;
+            }
+            let d = edge_to_edge_distance(
+                &shape.contours[a_contour].edges[a_edge],
+                &shape.contours[b_contour].edges[b_edge],
+                precision,
+            );
+            min_distance = min(min_distance, d);
+        }
+    }
+    min_distance
+}
+
+fn color_second_degree_graph(
+    coloring: &mut [usize],
+    edge_matrix: &[Vec<bool>],
+    vertex_count: usize,
+    seed: u64,
+) {
+    let mut seed = seed;
+    for i in 0..vertex_count {
+        let mut possible_colors = 7;
+        for j in 0..i {
+            if edge_matrix[i][j] {
+                possible_colors &= !(1 << coloring[j]);
+            }
+        }
+        coloring[i] = match possible_colors {
+            1 => 0,
+            2 => 1,
+            3 => seed_extract2(&mut seed) as usize, // 0 or 1
+            4 => 2,
+            5 => ((seed_extract2(&mut seed) == 0) as usize) << 1, // 2 or 0
+            6 => seed_extract2(&mut seed) as usize + 1,           // 1 or 2
+            7 => (seed_extract3(&mut seed) + i) % 3,              // 0 or 1 or 2
+            _ => 0,
+        };
+    }
+}
+
+fn vertex_possible_colors(coloring: &[usize], edge_vector: &[bool], vertex_count: usize) -> usize {
+    let mut used_colors = 0;
+    for i in 0..vertex_count {
+        if edge_vector[i] {
+            used_colors |= 1 << coloring[i];
+        }
+    }
+    7 & !used_colors
+}
+
+fn uncolor_same_neighbors(
+    uncolored: &mut VecDeque<usize>,
+    coloring: &mut [usize],
+    edge_matrix: &[Vec<bool>],
+    vertex: usize,
+    vertex_count: usize,
+) {
+    for i in (vertex + 1..vertex_count).chain(0..vertex) {
+        if edge_matrix[vertex][i] && coloring[i] == coloring[vertex] {
+            coloring[i] = UNCOLORED;
+            uncolored.push_back(i);
+        }
+    }
+}
+
+const UNCOLORED: usize = 3;
+const FIRST_POSSIBLE_COLOR: [usize; 8] = [UNCOLORED, 0, 1, 0, 2, 2, 1, 0];
+
+fn try_add_edge(
+    coloring: &mut [usize],
+    edge_matrix: &mut [Vec<bool>],
+    vertex_count: usize,
+    vertex_a: usize,
+    vertex_b: usize,
+    coloring_buffer: &mut [usize],
+) -> bool {
+    edge_matrix[vertex_a][vertex_b] = true;
+    edge_matrix[vertex_b][vertex_a] = true;
+    if coloring[vertex_a] != coloring[vertex_b] {
+        return true;
+    }
+    let b_possible_colors = vertex_possible_colors(coloring, &edge_matrix[vertex_b], vertex_count);
+    if b_possible_colors != 0 {
+        coloring[vertex_b] = FIRST_POSSIBLE_COLOR[b_possible_colors];
+        return true;
+    }
+    coloring_buffer.copy_from_slice(coloring);
+    let mut uncolored = VecDeque::new();
+    {
+        let coloring = &mut *coloring_buffer;
+        coloring[vertex_b] = FIRST_POSSIBLE_COLOR[7 & !(1 << coloring[vertex_a])];
+        uncolor_same_neighbors(
+            &mut uncolored,
+            coloring,
+            edge_matrix,
+            vertex_b,
+            vertex_count,
+        );
+        let mut step = 0;
+        while step < MAX_RECOLOR_STEPS {
+            let i = match uncolored.pop_front() {
+                Some(i) => i,
+                None => break,
+            };
+            let possible_colors = vertex_possible_colors(coloring, &edge_matrix[i], vertex_count);
+            if possible_colors != 0 {
+                coloring[i] = FIRST_POSSIBLE_COLOR[possible_colors];
+                continue;
+            }
+            loop {
+                coloring[i] = step % 3;
+                step += 1;
+                if !(edge_matrix[i][vertex_a] && coloring[i] == coloring[vertex_a]) {
+                    break;
+                }
+            }
+            uncolor_same_neighbors(&mut uncolored, coloring, edge_matrix, i, vertex_count);
+        }
+    }
+    if !uncolored.is_empty() {
+        edge_matrix[vertex_a][vertex_b] = false;
+        edge_matrix[vertex_b][vertex_a] = false;
+        return false;
+    }
+    coloring.copy_from_slice(coloring_buffer);
+    true
+}
+
+/// Assigns edge colors so that splines which are close to each other, including splines of
+/// different contours, are given different colors where possible. Considerably slower than
+/// [`edge_coloring_simple`].
+pub fn edge_coloring_by_distance(shape: &mut Shape, angle_threshold: f64, seed: u64) {
+    // Edges are referred to by (contour index, edge index)
+    let mut edge_segments: Vec<(usize, usize)> = Vec::new();
+    let mut spline_starts: Vec<usize> = Vec::new();
+
+    let cross_threshold = angle_threshold.sin();
+    let mut corners = Vec::new();
+    for (contour_index, contour) in shape.contours.iter_mut().enumerate() {
+        if contour.edges.is_empty() {
+            continue;
+        }
+        find_corners(contour, cross_threshold, &mut corners);
+
+        spline_starts.push(edge_segments.len());
+        if corners.is_empty() {
+            // Smooth contour
+            for index in 0..contour.edges.len() {
+                edge_segments.push((contour_index, index));
+            }
+        } else if corners.len() == 1 {
+            // "Teardrop" case
+            let corner = corners[0];
+            let m = contour.edges.len();
+            if m >= 3 {
+                for i in 0..m {
+                    if i == m / 2 {
+                        spline_starts.push(edge_segments.len());
+                    }
+                    let index = (corner + i) % m;
+                    if symmetrical_trichotomy(i, m) != 0 {
+                        edge_segments.push((contour_index, index));
+                    } else {
+                        contour.edges[index].color = EdgeColor::White;
+                    }
+                }
+            } else {
+                // Less than three edge segments for three colors => edges must be split
+                let colors = [EdgeColor::Black, EdgeColor::White, EdgeColor::Black];
+                split_teardrop(contour, corner, colors);
+                let part_count = contour.edges.len();
+                let spline_length = part_count / 3;
+                for index in 0..spline_length {
+                    edge_segments.push((contour_index, index));
+                }
+                spline_starts.push(edge_segments.len());
+                for index in part_count - spline_length..part_count {
+                    edge_segments.push((contour_index, index));
+                }
+            }
+        } else {
+            // Multiple corners
+            let corner_count = corners.len();
+            let mut spline = 0;
+            let start = corners[0];
+            let m = contour.edges.len();
+            for i in 0..m {
+                let index = (start + i) % m;
+                if spline + 1 < corner_count && corners[spline + 1] == index {
+                    spline_starts.push(edge_segments.len());
+                    spline += 1;
+                }
+                edge_segments.push((contour_index, index));
+            }
+        }
+    }
+    spline_starts.push(edge_segments.len());
+
+    let spline_count = spline_starts.len() - 1;
+    if spline_count == 0 {
+        return;
+    }
+
+    let mut distance_matrix = vec![vec![0.0; spline_count]; spline_count];
+    for i in 0..spline_count {
+        distance_matrix[i][i] = -1.0;
+        for j in i + 1..spline_count {
+            let dist = spline_to_spline_distance(
+                shape,
+                &edge_segments,
+                spline_starts[i]..spline_starts[i + 1],
+                spline_starts[j]..spline_starts[j + 1],
+                EDGE_DISTANCE_PRECISION,
+            );
+            distance_matrix[i][j] = dist;
+            distance_matrix[j][i] = dist;
+        }
+    }
+
+    let mut graph_edges: Vec<(usize, usize)> =
+        Vec::with_capacity(spline_count * (spline_count - 1) / 2);
+    for i in 0..spline_count {
+        for j in i + 1..spline_count {
+            graph_edges.push((i, j));
+        }
+    }
+    graph_edges.sort_by(|&(ai, aj), &(bi, bj)| {
+        distance_matrix[ai][aj]
+            .partial_cmp(&distance_matrix[bi][bj])
+            .unwrap_or(Ordering::Equal)
+    });
+
+    let mut edge_matrix = vec![vec![false; spline_count]; spline_count];
+    let mut next_edge = 0;
+    while next_edge < graph_edges.len() {
+        let (row, col) = graph_edges[next_edge];
+        if distance_matrix[row][col] != 0.0 {
+            break;
+        }
+        edge_matrix[row][col] = true;
+        edge_matrix[col][row] = true;
+        next_edge += 1;
+    }
+
+    let mut coloring = vec![0; spline_count];
+    let mut coloring_buffer = vec![0; spline_count];
+    color_second_degree_graph(&mut coloring, &edge_matrix, spline_count, seed);
+    for &(row, col) in &graph_edges[next_edge..] {
+        try_add_edge(
+            &mut coloring,
+            &mut edge_matrix,
+            spline_count,
+            row,
+            col,
+            &mut coloring_buffer,
+        );
+    }
+
+    const COLORS: [EdgeColor; 3] = [EdgeColor::Yellow, EdgeColor::Cyan, EdgeColor::Magenta];
+    let mut spline = 0;
+    for (i, &(contour_index, index)) in edge_segments.iter().enumerate() {
+        while spline_starts[spline + 1] == i {
+            spline += 1;
+        }
+        shape.contours[contour_index].edges[index].color = COLORS[coloring[spline]];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::tests::{add_polygon, add_rectangle, square};
+
+    fn teardrop() -> Shape {
+        let mut shape = Shape::default();
+        shape.add_empty_contour().add_edge(EdgeHolder::cubic(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(3.0, 1.0),
+            Vector2::new(3.0, -1.0),
+            Vector2::new(0.0, 0.0),
+            EdgeColor::White,
+        ));
+        shape
+    }
+
+    fn colors(shape: &Shape) -> Vec<Vec<EdgeColor>> {
+        shape
+            .contours
+            .iter()
+            .map(|contour| contour.edges.iter().map(|edge| edge.color).collect())
+            .collect()
+    }
+
+    /// Checks that every edge has at least two color channels, and that the two edges at each
+    /// corner share exactly one.
+    fn assert_corners_colored(shape: &Shape) {
+        let mut corners = Vec::new();
+        for contour in &shape.contours {
+            for edge in &contour.edges {
+                assert!(edge.color.bits().count_ones() >= 2, "{:?}", edge.color);
+            }
+            find_corners(contour, 3.0f64.sin(), &mut corners);
+            let m = contour.edges.len();
+            for &corner in &corners {
+                let prev = contour.edges[(corner + m - 1) % m].color;
+                let next = contour.edges[corner].color;
+                assert_ne!(prev, next);
+                assert_eq!((prev & next).bits().count_ones(), 1);
+            }
+        }
+    }
+
+    /// Checks that a teardrop was split into three edges, with a white edge between two
+    /// different colors.
+    fn assert_teardrop_split(shape: &Shape) {
+        let colors = &colors(shape)[0];
+        assert_eq!(colors.len(), 3);
+        assert_eq!(colors[1], EdgeColor::White);
+        assert_ne!(colors[0], colors[2]);
+        assert_ne!(colors[0], EdgeColor::White);
+        assert_ne!(colors[2], EdgeColor::White);
+    }
+
+    #[test]
+    fn simple_colors_corners() {
+        let mut shape = square(1.0);
+        edge_coloring_simple(&mut shape, 3.0, 0);
+        assert_corners_colored(&shape);
+    }
+
+    #[test]
+    fn simple_is_deterministic() {
+        for seed in [0, 1, 12345] {
+            let mut a = square(1.0);
+            let mut b = square(1.0);
+            edge_coloring_simple(&mut a, 3.0, seed);
+            edge_coloring_simple(&mut b, 3.0, seed);
+            assert_eq!(colors(&a), colors(&b));
+        }
+    }
+
+    #[test]
+    fn simple_colors_smooth_contour() {
+        let mut shape = Shape::default();
+        let contour = shape.add_empty_contour();
+        let (a, b) = (Vector2::new(0.0, 0.0), Vector2::new(2.0, 0.0));
+        let (up, down) = (Vector2::new(0.0, 1.3), Vector2::new(0.0, -1.3));
+        contour.add_edge(EdgeHolder::cubic(a, a + up, b + up, b, EdgeColor::White));
+        contour.add_edge(EdgeHolder::cubic(
+            b,
+            b + down,
+            a + down,
+            a,
+            EdgeColor::White,
+        ));
+        edge_coloring_simple(&mut shape, 3.0, 0);
+        let colors = &colors(&shape)[0];
+        assert_eq!(colors[0], colors[1]);
+        assert_eq!(colors[0].bits().count_ones(), 2);
+    }
+
+    #[test]
+    fn simple_splits_teardrop() {
+        let mut shape = teardrop();
+        edge_coloring_simple(&mut shape, 3.0, 0);
+        assert_teardrop_split(&shape);
+    }
+
+    /// A square with a small notch in its top edge, like an ink trap.
+    fn notched_square() -> Shape {
+        let mut shape = Shape::default();
+        add_polygon(
+            &mut shape,
+            &[
+                (0.0, 0.0),
+                (0.0, 10.0),
+                (4.9, 10.0),
+                (5.0, 9.8),
+                (5.1, 10.0),
+                (10.0, 10.0),
+                (10.0, 0.0),
+            ],
+        );
+        shape
+    }
+
+    #[test]
+    fn ink_trap_colors_corners() {
+        for mut shape in [square(1.0), notched_square()] {
+            edge_coloring_ink_trap(&mut shape, 3.0, 0);
+            assert_corners_colored(&shape);
+        }
+    }
+
+    #[test]
+    fn ink_trap_is_deterministic() {
+        for seed in [0, 1, 12345] {
+            let mut a = notched_square();
+            let mut b = notched_square();
+            edge_coloring_ink_trap(&mut a, 3.0, seed);
+            edge_coloring_ink_trap(&mut b, 3.0, seed);
+            assert_eq!(colors(&a), colors(&b));
+        }
+    }
+
+    #[test]
+    fn ink_trap_splits_teardrop() {
+        let mut shape = teardrop();
+        edge_coloring_ink_trap(&mut shape, 3.0, 0);
+        assert_teardrop_split(&shape);
+    }
+
+    #[test]
+    fn by_distance_colors_corners() {
+        let mut shape = square(1.0);
+        add_polygon(
+            &mut shape,
+            &[(0.2, 0.2), (0.8, 0.2), (0.8, 0.8), (0.2, 0.8)],
+        );
+        edge_coloring_by_distance(&mut shape, 3.0, 0);
+        assert_corners_colored(&shape);
+    }
+
+    #[test]
+    fn by_distance_is_deterministic() {
+        for seed in [0, 1, 12345] {
+            let mut a = notched_square();
+            let mut b = notched_square();
+            edge_coloring_by_distance(&mut a, 3.0, seed);
+            edge_coloring_by_distance(&mut b, 3.0, seed);
+            assert_eq!(colors(&a), colors(&b));
+        }
+    }
+
+    #[test]
+    fn by_distance_separates_close_contours() {
+        // The right edge of the first square is close to the left edge of the second one.
+        let mut shape = square(1.0);
+        add_rectangle(&mut shape, 1.1, 0.0, 2.1, 1.0);
+        edge_coloring_by_distance(&mut shape, 3.0, 0);
+        assert_corners_colored(&shape);
+        let colors = colors(&shape);
+        assert_ne!(colors[0][2], colors[1][0]);
+    }
+
+    #[test]
+    fn by_distance_splits_teardrop() {
+        let mut shape = teardrop();
+        edge_coloring_by_distance(&mut shape, 3.0, 0);
+        assert_teardrop_split(&shape);
+    }
+}
@@ -1,21 +1,48 @@
-use crate::{EdgeSegment, Vector2};
+use crate::{median, EdgeSegment, Vector2};
 
-/*struct MultiDistance {
-    r: f64,
-    g: f64,
-    b: f64,
+#[derive(Copy, Clone, Default, Debug)]
+pub struct MultiDistance {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
 }
 
-struct MultiAndTrueDistance {
-    multi: MultiDistance,
-    a: f64,
-}*/
+#[derive(Copy, Clone, Default, Debug)]
+pub struct MultiAndTrueDistance {
+    pub multi: MultiDistance,
+    pub a: f64,
+}
 
 pub(crate) const DISTANCE_DELTA_FACTOR: f64 = 1.001;
 
-pub trait EdgeSelector {
-    type Distance;
-    type Cache;
+pub trait DistanceType: Clone {
+    fn resolve(&self) -> f64;
+}
+
+impl DistanceType for f64 {
+    #[inline]
+    fn resolve(&self) -> f64 {
+        *self
+    }
+}
+
+impl DistanceType for MultiDistance {
+    #[inline]
+    fn resolve(&self) -> f64 {
+        median(self.r, self.g, self.b)
+    }
+}
+
+impl DistanceType for MultiAndTrueDistance {
+    #[inline]
+    fn resolve(&self) -> f64 {
+        self.multi.resolve()
+    }
+}
+
+pub trait EdgeSelector: Default {
+    type Distance: DistanceType;
+    type Cache: Default;
 
     fn reset(&mut self, p: Vector2);
     fn distance(&self, edges: &[EdgeSegment]) -> Self::Distance;
@@ -0,0 +1,263 @@
+use crate::{
+    dot_product, fabs, get_pseudo_distance, EdgeColor, EdgeSegment, EdgeSelector,
+    MultiAndTrueDistance, MultiDistance, PseudoDistanceCache, PseudoDistanceSelector,
+    SignedDistance, Vector2, DISTANCE_DELTA_FACTOR,
+};
+
+#[derive(Default)]
+pub struct MultiDistanceSelector {
+    p: Vector2,
+    r: PseudoDistanceSelector,
+    g: PseudoDistanceSelector,
+    b: PseudoDistanceSelector,
+}
+
+impl EdgeSelector for MultiDistanceSelector {
+    type Distance = MultiDistance;
+    type Cache = PseudoDistanceCache;
+
+    fn reset(&mut self, p: Vector2) {
+        let delta = DISTANCE_DELTA_FACTOR * (p - self.p).length();
+        self.r.reset(delta);
+        self.g.reset(delta);
+        self.b.reset(delta);
+        self.p = p;
+    }
+
+    fn distance(&self, edges: &[EdgeSegment]) -> Self::Distance {
+        MultiDistance {
+            r: self.r.compute_distance(self.p, edges),
+            g: self.g.compute_distance(self.p, edges),
+            b: self.b.compute_distance(self.p, edges),
+        }
+    }
+
+    fn add_edge(
+        &mut self,
+        cache: &mut Self::Cache,
+        edges: &[EdgeSegment],
+        prev_edge: usize,
+        edge: usize,
+        next_edge: usize,
+    ) {
+        let color = edges[edge].color;
+        let has_red = color & EdgeColor::Red != EdgeColor::Black;
+        let has_green = color & EdgeColor::Green != EdgeColor::Black;
+        let has_blue = color & EdgeColor::Blue != EdgeColor::Black;
+        if (has_red && self.r.is_edge_relevant(cache, &edges[edge], self.p))
+            || (has_green && self.g.is_edge_relevant(cache, &edges[edge], self.p))
+            || (has_blue && self.b.is_edge_relevant(cache, &edges[edge], self.p))
+        {
+            let mut param = 0.0;
+            let distance = edges[edge].signed_distance(self.p, &mut param);
+            if has_red {
+                self.r.add_edge_true_distance(edge, &distance, param);
+            }
+            if has_green {
+                self.g.add_edge_true_distance(edge, &distance, param);
+            }
+            if has_blue {
+                self.b.add_edge_true_distance(edge, &distance, param);
+            }
+            cache.point = self.p;
+            cache.abs_distance = fabs(distance.distance);
+
+            let ap = self.p - edges[edge].point(0.0);
+            let bp = self.p - edges[edge].point(1.0);
+            let a_dir = edges[edge].direction(0.0).normalize_allow_zero();
+            let b_dir = edges[edge].direction(1.0).normalize_allow_zero();
+            let prev_dir = edges[prev_edge].direction(1.0).normalize_allow_zero();
+            let next_dir = edges[next_edge].direction(0.0).normalize_allow_zero();
+            let add = dot_product(ap, (prev_dir + a_dir).normalize_allow_zero());
+            let bdd = -dot_product(bp, (b_dir + next_dir).normalize_allow_zero());
+
+            if add > 0.0 {
+                let mut pd = distance.distance;
+                if get_pseudo_distance(&mut pd, ap, -a_dir) {
+                    pd = -pd;
+                    self.add_edge_pseudo_distance(color, pd);
+                }
+                cache.a_pseudo_distance = pd;
+            }
+            if bdd > 0.0 {
+                let mut pd = distance.distance;
+                if get_pseudo_distance(&mut pd, bp, b_dir) {
+                    self.add_edge_pseudo_distance(color, pd);
+                }
+                cache.b_pseudo_distance = pd;
+            }
+            cache.a_domain_distance = add;
+            cache.b_domain_distance = bdd;
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.r.merge(&other.r);
+        self.g.merge(&other.g);
+        self.b.merge(&other.b);
+    }
+}
+
+impl MultiDistanceSelector {
+    fn add_edge_pseudo_distance(&mut self, color: EdgeColor, distance: f64) {
+        if color & EdgeColor::Red != EdgeColor::Black {
+            self.r.add_edge_pseudo_distance(distance);
+        }
+        if color & EdgeColor::Green != EdgeColor::Black {
+            self.g.add_edge_pseudo_distance(distance);
+        }
+        if color & EdgeColor::Blue != EdgeColor::Black {
+            self.b.add_edge_pseudo_distance(distance);
+        }
+    }
+
+    pub fn true_distance(&self) -> SignedDistance {
+        let mut distance = self.r.true_distance();
+        if self.g.true_distance() < distance {
+            distance = self.g.true_distance();
+        }
+        if self.b.true_distance() < distance {
+            distance = self.b.true_distance();
+        }
+        distance
+    }
+}
+
+#[derive(Default)]
+pub struct MultiAndTrueDistanceSelector {
+    multi: MultiDistanceSelector,
+}
+
+impl EdgeSelector for MultiAndTrueDistanceSelector {
+    type Distance = MultiAndTrueDistance;
+    type Cache = PseudoDistanceCache;
+
+    #[inline]
+    fn reset(&mut self, p: Vector2) {
+        self.multi.reset(p);
+    }
+
+    fn distance(&self, edges: &[EdgeSegment]) -> Self::Distance {
+        MultiAndTrueDistance {
+            multi: self.multi.distance(edges),
+            a: self.multi.true_distance().distance,
+        }
+    }
+
+    #[inline]
+    fn add_edge(
+        &mut self,
+        cache: &mut Self::Cache,
+        edges: &[EdgeSegment],
+        prev_edge: usize,
+        edge: usize,
+        next_edge: usize,
+    ) {
+        self.multi
+            .add_edge(cache, edges, prev_edge, edge, next_edge);
+    }
+
+    #[inline]
+    fn merge(&mut self, other: &Self) {
+        self.multi.merge(&other.multi);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::tests::add_rectangle;
+    use crate::{
+        DistanceType, OverlappingContourCombiner, Shape, ShapeDistanceFinder, SimpleContourCombiner,
+    };
+
+    /// Adds a rectangle of unit height whose edges are colored so that adjacent edges share one
+    /// channel.
+    fn colored_square(shape: &mut Shape, l: f64, r: f64) {
+        let colors = [
+            EdgeColor::Yellow,
+            EdgeColor::Cyan,
+            EdgeColor::Magenta,
+            EdgeColor::Cyan,
+        ];
+        let contour = add_rectangle(shape, l, 0.0, r, 1.0);
+        for (edge, color) in contour.edges.iter_mut().zip(colors) {
+            edge.color = color;
+        }
+    }
+
+    fn unit_square() -> Shape {
+        let mut shape = Shape::default();
+        colored_square(&mut shape, 0.0, 1.0);
+        shape
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{a} != {b}");
+    }
+
+    #[test]
+    fn multi_distance_of_square() {
+        let shape = unit_square();
+        let mut finder =
+            ShapeDistanceFinder::<SimpleContourCombiner<MultiDistanceSelector>>::new(&shape);
+
+        let distance = finder.distance(Vector2::new(0.5, 0.5));
+        assert_close(distance.r, 0.5);
+        assert_close(distance.g, 0.5);
+        assert_close(distance.b, 0.5);
+
+        // Only the blue channel ignores the left edge.
+        let distance = finder.distance(Vector2::new(0.25, 0.5));
+        assert_close(distance.r, 0.25);
+        assert_close(distance.g, 0.25);
+        assert_close(distance.b, 0.5);
+        assert_close(distance.resolve(), 0.25);
+
+        let distance = finder.distance(Vector2::new(2.0, 0.5));
+        assert_close(distance.r, -1.0);
+        assert_close(distance.resolve(), -1.0);
+    }
+
+    #[test]
+    fn multi_distance_of_overlapping_contours() {
+        let mut shape = unit_square();
+        colored_square(&mut shape, 0.5, 1.5);
+        let p = Vector2::new(0.9, 0.5);
+        let simple =
+            ShapeDistanceFinder::<SimpleContourCombiner<MultiDistanceSelector>>::one_shot_distance(
+                &shape, p,
+            );
+        let overlapping = ShapeDistanceFinder::<
+            OverlappingContourCombiner<MultiDistanceSelector>,
+        >::one_shot_distance(&shape, p);
+        assert_close(simple.resolve(), 0.1);
+        assert_close(overlapping.resolve(), 0.4);
+    }
+
+    #[test]
+    fn multi_and_true_distance_of_square() {
+        type Finder = ShapeDistanceFinder<SimpleContourCombiner<MultiAndTrueDistanceSelector>>;
+        let shape = unit_square();
+        let mut finder = Finder::new(&shape);
+
+        let distance = finder.distance(Vector2::new(0.25, 0.5));
+        assert_close(distance.resolve(), 0.25);
+        assert_close(distance.a, 0.25);
+
+        // Past the corner, the channels hold pseudo-distances while alpha holds the true one.
+        let distance = finder.distance(Vector2::new(2.0, 2.0));
+        assert_close(distance.resolve(), -1.0);
+        assert_close(distance.a, -2.0f64.sqrt());
+    }
+
+    #[test]
+    fn multi_and_true_distance_of_overlapping_contours() {
+        type Finder = ShapeDistanceFinder<OverlappingContourCombiner<MultiAndTrueDistanceSelector>>;
+        let mut shape = unit_square();
+        colored_square(&mut shape, 0.5, 1.5);
+        let distance = Finder::one_shot_distance(&shape, Vector2::new(0.9, 0.5));
+        assert_close(distance.resolve(), 0.4);
+        assert_close(distance.a, 0.4);
+    }
+}
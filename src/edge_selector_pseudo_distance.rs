@@ -13,18 +13,32 @@ pub struct PseudoDistanceSelector {
     p: Vector2,
 }
 
+impl Default for PseudoDistanceSelector {
+    fn default() -> Self {
+        let min_true_distance = SignedDistance::infinite();
+        Self {
+            min_negative_pseudo_distance: -fabs(min_true_distance.distance),
+            min_positive_pseudo_distance: fabs(min_true_distance.distance),
+            min_true_distance,
+            near_edge_index: None,
+            near_edge_param: 0.0,
+            p: Vector2::default(),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct PseudoDistanceCache {
-    point: Vector2,
-    abs_distance: f64,
-    a_domain_distance: f64,
-    b_domain_distance: f64,
-    a_pseudo_distance: f64,
-    b_pseudo_distance: f64,
+    pub(crate) point: Vector2,
+    pub(crate) abs_distance: f64,
+    pub(crate) a_domain_distance: f64,
+    pub(crate) b_domain_distance: f64,
+    pub(crate) a_pseudo_distance: f64,
+    pub(crate) b_pseudo_distance: f64,
 }
 
 #[inline]
-fn get_pseudo_distance(distance: &mut f64, ep: Vector2, edge_dir: Vector2) -> bool {
+pub(crate) fn get_pseudo_distance(distance: &mut f64, ep: Vector2, edge_dir: Vector2) -> bool {
     let ts = dot_product(ep, edge_dir);
     if ts > 0.0 {
         let pseudo_distance = cross_product(ep, edge_dir);
@@ -96,12 +110,12 @@ impl EdgeSelector for PseudoDistanceSelector {
     }
 
     fn merge(&mut self, other: &Self) {
-        todo!()
+        self.merge(other);
     }
 }
 
 impl PseudoDistanceSelector {
-    fn reset(&mut self, delta: f64) {
+    pub(crate) fn reset(&mut self, delta: f64) {
         self.min_true_distance.distance +=
             non_zero_sign::<f64, f64>(self.min_true_distance.distance) * delta;
         self.min_negative_pseudo_distance = -fabs(self.min_true_distance.distance);
@@ -110,7 +124,7 @@ impl PseudoDistanceSelector {
         self.near_edge_param = 0.0;
     }
 
-    fn is_edge_relevant(
+    pub(crate) fn is_edge_relevant(
         &self,
         cache: &PseudoDistanceCache,
         edge: &EdgeSegment,
@@ -147,7 +161,12 @@ impl PseudoDistanceSelector {
         false
     }
 
-    fn add_edge_true_distance(&mut self, edge_index: usize, distance: &SignedDistance, param: f64) {
+    pub(crate) fn add_edge_true_distance(
+        &mut self,
+        edge_index: usize,
+        distance: &SignedDistance,
+        param: f64,
+    ) {
         if distance < &self.min_true_distance {
             self.min_true_distance = distance.clone();
             self.near_edge_index = Some(edge_index);
@@ -155,7 +174,7 @@ impl PseudoDistanceSelector {
         }
     }
 
-    fn add_edge_pseudo_distance(&mut self, distance: f64) {
+    pub(crate) fn add_edge_pseudo_distance(&mut self, distance: f64) {
         if distance <= 0.0 && distance > self.min_negative_pseudo_distance {
             self.min_negative_pseudo_distance = distance;
         }
@@ -164,7 +183,7 @@ impl PseudoDistanceSelector {
         }
     }
 
-    fn merge(&mut self, other: &PseudoDistanceSelector) {
+    pub(crate) fn merge(&mut self, other: &PseudoDistanceSelector) {
         if other.min_true_distance < self.min_true_distance {
             self.min_true_distance = other.min_true_distance.clone();
             self.near_edge_index = other.near_edge_index;
@@ -178,7 +197,7 @@ impl PseudoDistanceSelector {
         }
     }
 
-    fn compute_distance(&self, p: Vector2, edges: &[EdgeSegment]) -> f64 {
+    pub(crate) fn compute_distance(&self, p: Vector2, edges: &[EdgeSegment]) -> f64 {
         let mut min_distance = if self.min_true_distance.distance < 0.0 {
             self.min_negative_pseudo_distance
         } else {
@@ -198,7 +217,7 @@ impl PseudoDistanceSelector {
         min_distance
     }
 
-    fn true_distance(&self) -> SignedDistance {
+    pub(crate) fn true_distance(&self) -> SignedDistance {
         self.min_true_distance.clone()
     }
 }
@@ -7,6 +7,16 @@ pub struct TrueDistanceSelector {
     min_distance: SignedDistance,
 }
 
+impl Default for TrueDistanceSelector {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            p: Vector2::default(),
+            min_distance: SignedDistance::infinite(),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct TrueDistanceCache {
     point: Vector2,
@@ -0,0 +1,662 @@
+use crate::font_cff::Cff;
+use crate::{Contour, EdgeColor, EdgeHolder, Shape, Vector2};
+use std::fmt;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FontError {
+    /// The data ends in the middle of a structure.
+    UnexpectedEnd,
+    /// The data is not a TrueType or OpenType font or font collection.
+    UnknownFormat,
+    /// The font index is out of range for the font collection.
+    InvalidFontIndex(usize),
+    /// A table that is required for the operation is missing.
+    MissingTable([u8; 4]),
+    /// A table is malformed, or uses a version or format that is not supported.
+    InvalidTable([u8; 4]),
+    /// The glyph index is out of range for the font.
+    InvalidGlyph(u16),
+    /// The font has no variation axis with this tag.
+    UnknownAxis([u8; 4]),
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::UnexpectedEnd => write!(f, "unexpected end of font data"),
+            FontError::UnknownFormat => write!(f, "unknown font format"),
+            FontError::InvalidFontIndex(index) => write!(f, "font index {index} out of range"),
+            FontError::MissingTable(tag) => {
+                write!(f, "missing table '{}'", String::from_utf8_lossy(tag))
+            }
+            FontError::InvalidTable(tag) => {
+                write!(f, "invalid table '{}'", String::from_utf8_lossy(tag))
+            }
+            FontError::InvalidGlyph(glyph) => write!(f, "glyph index {glyph} out of range"),
+            FontError::UnknownAxis(tag) => {
+                write!(
+                    f,
+                    "unknown variation axis '{}'",
+                    String::from_utf8_lossy(tag)
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+/// Reads big-endian values from font data.
+#[derive(Clone)]
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    #[inline]
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    #[inline]
+    pub(crate) fn at(data: &'a [u8], pos: usize) -> Self {
+        Self { data, pos }
+    }
+
+    #[inline]
+    pub(crate) fn pos(&self) -> usize {
+        self.pos
+    }
+
+    #[inline]
+    pub(crate) fn seek(&mut self, pos: usize) {
+        self.pos = pos;
+    }
+
+    #[inline]
+    pub(crate) fn skip(&mut self, len: usize) {
+        self.pos = self.pos.saturating_add(len);
+    }
+
+    pub(crate) fn bytes(&mut self, len: usize) -> Result<&'a [u8], FontError> {
+        let end = self.pos.checked_add(len).ok_or(FontError::UnexpectedEnd)?;
+        let bytes = self
+            .data
+            .get(self.pos..end)
+            .ok_or(FontError::UnexpectedEnd)?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    #[inline]
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], FontError> {
+        Ok(self.bytes(N)?.try_into().unwrap())
+    }
+
+    #[inline]
+    pub(crate) fn u8(&mut self) -> Result<u8, FontError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    #[inline]
+    pub(crate) fn i8(&mut self) -> Result<i8, FontError> {
+        Ok(self.u8()? as i8)
+    }
+
+    #[inline]
+    pub(crate) fn u16(&mut self) -> Result<u16, FontError> {
+        Ok(u16::from_be_bytes(self.array()?))
+    }
+
+    #[inline]
+    pub(crate) fn i16(&mut self) -> Result<i16, FontError> {
+        Ok(i16::from_be_bytes(self.array()?))
+    }
+
+    #[inline]
+    pub(crate) fn u32(&mut self) -> Result<u32, FontError> {
+        Ok(u32::from_be_bytes(self.array()?))
+    }
+
+    #[inline]
+    pub(crate) fn i32(&mut self) -> Result<i32, FontError> {
+        Ok(i32::from_be_bytes(self.array()?))
+    }
+
+    #[inline]
+    pub(crate) fn tag(&mut self) -> Result<[u8; 4], FontError> {
+        self.array()
+    }
+
+    /// Reads a 2.14 fixed-point number.
+    #[inline]
+    pub(crate) fn f2dot14(&mut self) -> Result<f64, FontError> {
+        Ok(self.i16()? as f64 / 16384.0)
+    }
+
+    /// Reads a 16.16 fixed-point number.
+    #[inline]
+    pub(crate) fn fixed(&mut self) -> Result<f64, FontError> {
+        Ok(self.i32()? as f64 / 65536.0)
+    }
+}
+
+/// Builds a shape from a glyph outline, skipping zero-length lines and empty contours.
+pub(crate) struct OutlineBuilder {
+    shape: Shape,
+    contour: Contour,
+    start: Vector2,
+    current: Vector2,
+}
+
+impl OutlineBuilder {
+    pub(crate) fn new() -> Self {
+        Self {
+            shape: Shape::default(),
+            contour: Contour::default(),
+            start: Vector2::default(),
+            current: Vector2::default(),
+        }
+    }
+
+    pub(crate) fn move_to(&mut self, p: Vector2) {
+        self.close();
+        self.start = p;
+        self.current = p;
+    }
+
+    pub(crate) fn line_to(&mut self, p: Vector2) {
+        if p != self.current {
+            self.contour
+                .add_edge(EdgeHolder::linear(self.current, p, EdgeColor::White));
+        }
+        self.current = p;
+    }
+
+    pub(crate) fn quadratic_to(&mut self, p1: Vector2, p2: Vector2) {
+        self.contour.add_edge(EdgeHolder::quadratic(
+            self.current,
+            p1,
+            p2,
+            EdgeColor::White,
+        ));
+        self.current = p2;
+    }
+
+    pub(crate) fn cubic_to(&mut self, p1: Vector2, p2: Vector2, p3: Vector2) {
+        self.contour.add_edge(EdgeHolder::cubic(
+            self.current,
+            p1,
+            p2,
+            p3,
+            EdgeColor::White,
+        ));
+        self.current = p3;
+    }
+
+    /// Closes the current contour with a line back to its start.
+    pub(crate) fn close(&mut self) {
+        self.line_to(self.start);
+        let contour = std::mem::take(&mut self.contour);
+        if !contour.edges.is_empty() {
+            self.shape.add_contour(contour);
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> Shape {
+        self.close();
+        self.shape
+    }
+}
+
+/// The location of a character map subtable, and how to interpret it.
+#[derive(Clone, Copy)]
+struct CharacterMap {
+    offset: usize,
+    format: u16,
+    /// Symbol fonts map their characters into the private use area at U+F000.
+    symbol: bool,
+}
+
+/// The horizontal metrics of a glyph, in font units.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GlyphMetrics {
+    pub advance_width: u16,
+    pub left_side_bearing: i16,
+}
+
+/// A TrueType or OpenType font, parsed from its file contents. Glyph shapes are given in font
+/// units, with the y-axis pointing up.
+pub struct Font<'a> {
+    data: &'a [u8],
+    /// The offset of the table directory.
+    directory: usize,
+    units_per_em: u16,
+    index_to_loc_format: i16,
+    num_glyphs: u16,
+    num_h_metrics: u16,
+    cmap: Option<CharacterMap>,
+    cff: Option<Cff<'a>>,
+    /// The normalized coordinates of the selected instance of a variable font, which are empty
+    /// for the default instance.
+    pub(crate) coords: Vec<f64>,
+}
+
+impl<'a> Font<'a> {
+    /// Parses a font file, or the first font of a font collection.
+    pub fn from_bytes(data: &'a [u8]) -> Result<Font<'a>, FontError> {
+        Self::from_collection(data, 0)
+    }
+
+    /// Parses the font at `index` in a TrueType collection (`.ttc`). A font file that is not
+    /// a collection is treated as a collection of one font.
+    pub fn from_collection(data: &'a [u8], index: usize) -> Result<Font<'a>, FontError> {
+        let mut reader = Reader::new(data);
+        let directory = match &reader.tag()? {
+            b"ttcf" => {
+                reader.skip(4);
+                let num_fonts = reader.u32()? as usize;
+                if index >= num_fonts {
+                    return Err(FontError::InvalidFontIndex(index));
+                }
+                reader.skip(index * 4);
+                reader.u32()? as usize
+            }
+            [0, 1, 0, 0] | b"OTTO" | b"true" => {
+                if index != 0 {
+                    return Err(FontError::InvalidFontIndex(index));
+                }
+                0
+            }
+            _ => return Err(FontError::UnknownFormat),
+        };
+        let mut font = Font {
+            data,
+            directory,
+            units_per_em: 0,
+            index_to_loc_format: 0,
+            num_glyphs: 0,
+            num_h_metrics: 0,
+            cmap: None,
+            cff: None,
+            coords: Vec::new(),
+        };
+        let head = font.required_table(b"head")?;
+        let mut reader = Reader::at(head, 18);
+        font.units_per_em = reader.u16()?;
+        reader.seek(50);
+        font.index_to_loc_format = reader.i16()?;
+        font.num_glyphs = Reader::at(font.required_table(b"maxp")?, 4).u16()?;
+        if let Some(hhea) = font.table(b"hhea") {
+            font.num_h_metrics = Reader::at(hhea, 34).u16()?;
+        }
+        if let Some(cmap) = font.table(b"cmap") {
+            font.cmap = find_character_map(cmap)?;
+        }
+        if let Some(cff2) = font.table(b"CFF2") {
+            font.cff = Some(Cff::parse(cff2, true)?);
+        } else if let Some(cff) = font.table(b"CFF ") {
+            font.cff = Some(Cff::parse(cff, false)?);
+        }
+        Ok(font)
+    }
+
+    /// Returns the contents of the table with the given tag, if the font has it.
+    pub(crate) fn table(&self, tag: &[u8; 4]) -> Option<&'a [u8]> {
+        let mut reader = Reader::at(self.data, self.directory + 4);
+        let num_tables = reader.u16().ok()?;
+        reader.skip(6);
+        for _ in 0..num_tables {
+            let record_tag = reader.tag().ok()?;
+            reader.skip(4);
+            let offset = reader.u32().ok()? as usize;
+            let length = reader.u32().ok()? as usize;
+            if &record_tag == tag {
+                return self.data.get(offset..offset.checked_add(length)?);
+            }
+        }
+        None
+    }
+
+    pub(crate) fn required_table(&self, tag: &[u8; 4]) -> Result<&'a [u8], FontError> {
+        self.table(tag).ok_or(FontError::MissingTable(*tag))
+    }
+
+    #[inline]
+    pub(crate) fn index_to_loc_format(&self) -> i16 {
+        self.index_to_loc_format
+    }
+
+    /// The size of the em square, in font units.
+    #[inline]
+    pub fn units_per_em(&self) -> u16 {
+        self.units_per_em
+    }
+
+    #[inline]
+    pub fn num_glyphs(&self) -> u16 {
+        self.num_glyphs
+    }
+
+    pub(crate) fn check_glyph(&self, glyph: u16) -> Result<(), FontError> {
+        if glyph < self.num_glyphs {
+            Ok(())
+        } else {
+            Err(FontError::InvalidGlyph(glyph))
+        }
+    }
+
+    /// Looks up the glyph of a character in the font's character map. Returns `None` if the
+    /// character is not mapped.
+    pub fn glyph_index(&self, c: char) -> Option<u16> {
+        let cmap = self.cmap?;
+        let data = &self.table(b"cmap")?[cmap.offset..];
+        let mut c = c as u32;
+        if cmap.symbol && c < 0x100 {
+            c += 0xF000;
+        }
+        let glyph = match cmap.format {
+            4 => lookup_format_4(data, c),
+            12 => lookup_format_12(data, c),
+            _ => None,
+        };
+        glyph.filter(|&glyph| glyph != 0 && glyph < self.num_glyphs)
+    }
+
+    /// Returns the advance width and left side bearing of the glyph from the `hmtx` table, in
+    /// font units. For a variable font, they are adjusted to the instance selected with
+    /// [`Font::set_variations`] by the glyph's phantom points in `gvar`. Fonts without TrueType
+    /// outlines keep the metrics of the default instance, since `HVAR` is not supported.
+    pub fn glyph_metrics(&self, glyph: u16) -> Option<GlyphMetrics> {
+        let hmtx = self.table(b"hmtx")?;
+        let num_h_metrics = self.num_h_metrics as usize;
+        let glyph_index = glyph as usize;
+        // Glyphs past the last metric share its advance, but have bearings of their own.
+        let index = glyph_index.min(num_h_metrics.checked_sub(1)?);
+        let advance = Reader::at(hmtx, index * 4).u16().ok()?;
+        let offset = if glyph_index < num_h_metrics {
+            glyph_index * 4 + 2
+        } else {
+            num_h_metrics * 4 + (glyph_index - num_h_metrics) * 2
+        };
+        let bearing = Reader::at(hmtx, offset).i16().ok()?;
+        let (advance_delta, bearing_delta) = if self.coords.is_empty() {
+            (0.0, 0.0)
+        } else {
+            self.horizontal_metrics_deltas(glyph).unwrap_or_default()
+        };
+        Some(GlyphMetrics {
+            advance_width: (advance as f64 + advance_delta)
+                .round()
+                .clamp(0.0, u16::MAX as f64) as u16,
+            left_side_bearing: (bearing as f64 + bearing_delta)
+                .round()
+                .clamp(i16::MIN as f64, i16::MAX as f64) as i16,
+        })
+    }
+
+    /// Returns the horizontal advance of the glyph. See [`Font::glyph_metrics`].
+    #[inline]
+    pub fn advance_width(&self, glyph: u16) -> Option<u16> {
+        self.glyph_metrics(glyph)
+            .map(|metrics| metrics.advance_width)
+    }
+
+    /// Returns the left side bearing of the glyph. See [`Font::glyph_metrics`].
+    #[inline]
+    pub fn left_side_bearing(&self, glyph: u16) -> Option<i16> {
+        self.glyph_metrics(glyph)
+            .map(|metrics| metrics.left_side_bearing)
+    }
+
+    /// Builds the shape of the glyph's outline, in font units, from either TrueType (`glyf`)
+    /// or CFF (`CFF ` and `CFF2`) outlines. Glyphs without an outline, such as the space,
+    /// produce an empty shape. For a variable font, the shape is that of the instance selected
+    /// with [`Font::set_variations`].
+    pub fn glyph_shape(&self, glyph: u16) -> Result<Shape, FontError> {
+        self.check_glyph(glyph)?;
+        if self.table(b"glyf").is_some() {
+            self.truetype_glyph_shape(glyph)
+        } else if let Some(cff) = &self.cff {
+            cff.glyph_shape(glyph, &self.coords)
+        } else {
+            Err(FontError::MissingTable(*b"glyf"))
+        }
+    }
+}
+
+/// Chooses the subtable of the `cmap` table that covers the most characters: a full Unicode
+/// map in format 12 if there is one, then a BMP map in format 4, then a symbol map.
+fn find_character_map(cmap: &[u8]) -> Result<Option<CharacterMap>, FontError> {
+    let mut reader = Reader::at(cmap, 2);
+    let num_tables = reader.u16()?;
+    let mut best: Option<(u8, CharacterMap)> = None;
+    for _ in 0..num_tables {
+        let platform = reader.u16()?;
+        let encoding = reader.u16()?;
+        let offset = reader.u32()? as usize;
+        let format = Reader::at(cmap, offset).u16()?;
+        let priority = match (platform, encoding, format) {
+            (0, 4..=6, 12) | (3, 10, 12) => 3,
+            (0, 0..=3, 4) | (3, 1, 4) => 2,
+            (3, 0, 4) => 1,
+            _ => continue,
+        };
+        if best.is_none_or(|(p, _)| priority > p) {
+            let symbol = platform == 3 && encoding == 0;
+            best = Some((
+                priority,
+                CharacterMap {
+                    offset,
+                    format,
+                    symbol,
+                },
+            ));
+        }
+    }
+    Ok(best.map(|(_, cmap)| cmap))
+}
+
+fn lookup_format_4(data: &[u8], c: u32) -> Option<u16> {
+    let c = u16::try_from(c).ok()?;
+    let seg_count = Reader::at(data, 6).u16().ok()? as usize / 2;
+    let end_codes = 14;
+    let start_codes = end_codes + seg_count * 2 + 2;
+    let id_deltas = start_codes + seg_count * 2;
+    let id_range_offsets = id_deltas + seg_count * 2;
+    // Find the first segment whose end code is at least c.
+    let (mut lo, mut hi) = (0, seg_count);
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if Reader::at(data, end_codes + mid * 2).u16().ok()? < c {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    let segment = lo;
+    if segment >= seg_count {
+        return None;
+    }
+    let start = Reader::at(data, start_codes + segment * 2).u16().ok()?;
+    if c < start {
+        return None;
+    }
+    let delta = Reader::at(data, id_deltas + segment * 2).u16().ok()?;
+    let range_offset_pos = id_range_offsets + segment * 2;
+    let range_offset = Reader::at(data, range_offset_pos).u16().ok()? as usize;
+    if range_offset == 0 {
+        return Some(c.wrapping_add(delta));
+    }
+    let glyph_pos = range_offset_pos + range_offset + (c - start) as usize * 2;
+    match Reader::at(data, glyph_pos).u16().ok()? {
+        0 => None,
+        glyph => Some(glyph.wrapping_add(delta)),
+    }
+}
+
+fn lookup_format_12(data: &[u8], c: u32) -> Option<u16> {
+    let num_groups = Reader::at(data, 12).u32().ok()? as usize;
+    let (mut lo, mut hi) = (0, num_groups);
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        let mut reader = Reader::at(data, 16 + mid * 12);
+        let start = reader.u32().ok()?;
+        let end = reader.u32().ok()?;
+        if c < start {
+            hi = mid;
+        } else if c > end {
+            lo = mid + 1;
+        } else {
+            let glyph = reader.u32().ok()? + (c - start);
+            return u16::try_from(glyph).ok();
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Assembles a font with `num_glyphs` glyphs, 1000 units per em and long `loca` offsets
+    /// from the given tables, adding the `head` and `maxp` tables.
+    pub(crate) fn font_data(num_glyphs: u16, tables: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+        let mut head = [0; 54];
+        head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+        head[50..52].copy_from_slice(&1i16.to_be_bytes());
+        let mut maxp = vec![0, 0, 0x50, 0];
+        maxp.extend(num_glyphs.to_be_bytes());
+        let tables: Vec<(&[u8; 4], &[u8])> = [(b"head", &head[..]), (b"maxp", &maxp[..])]
+            .into_iter()
+            .chain(tables.iter().copied())
+            .collect();
+        let mut data = vec![0, 1, 0, 0];
+        data.extend((tables.len() as u16).to_be_bytes());
+        data.extend([0; 6]);
+        let mut offset = 12 + 16 * tables.len();
+        for (tag, table) in &tables {
+            data.extend(*tag);
+            data.extend([0; 4]);
+            data.extend((offset as u32).to_be_bytes());
+            data.extend((table.len() as u32).to_be_bytes());
+            offset += table.len();
+        }
+        for (_, table) in &tables {
+            data.extend(*table);
+        }
+        data
+    }
+
+    pub(crate) fn words(values: &[i32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|&v| (v as u16).to_be_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn reads_font_header() {
+        let data = font_data(3, &[]);
+        let font = Font::from_bytes(&data).unwrap();
+        assert_eq!(font.units_per_em(), 1000);
+        assert_eq!(font.num_glyphs(), 3);
+        assert_eq!(font.index_to_loc_format(), 1);
+        assert_eq!(font.glyph_index('A'), None);
+        assert_eq!(
+            Font::from_collection(&data, 1).err(),
+            Some(FontError::InvalidFontIndex(1))
+        );
+        assert_eq!(
+            Font::from_bytes(b"wOFF").err(),
+            Some(FontError::UnknownFormat)
+        );
+    }
+
+    #[test]
+    fn looks_up_format_4() {
+        // Segments mapping 'A'..='C' by a delta, 0x100..=0x102 through the glyph array with a
+        // delta of 1, and the final 0xFFFF segment.
+        let mut data = words(&[4, 0, 0, 6, 4, 1, 2]);
+        data.extend(words(&[0x43, 0x102, 0xFFFF, 0]));
+        data.extend(words(&[0x41, 0x100, 0xFFFF]));
+        data.extend(words(&[10 - 0x41, 1, 1]));
+        data.extend(words(&[0, 4, 0]));
+        data.extend(words(&[20, 0, 22]));
+        let lookup = |c| lookup_format_4(&data, c);
+        assert_eq!(lookup(0x40), None);
+        assert_eq!(lookup(0x41), Some(10));
+        assert_eq!(lookup(0x43), Some(12));
+        assert_eq!(lookup(0x44), None);
+        assert_eq!(lookup(0x100), Some(21));
+        assert_eq!(lookup(0x101), None);
+        assert_eq!(lookup(0x102), Some(23));
+        assert_eq!(lookup(0x103), None);
+        assert_eq!(lookup(0x10000), None);
+    }
+
+    #[test]
+    fn looks_up_format_12() {
+        let mut data = words(&[12, 0, 0, 0, 0, 0]);
+        data.extend(3u32.to_be_bytes());
+        for group in [
+            [0x41, 0x43, 10],
+            [0x1F600, 0x1F602, 500],
+            [0x20000, 0x20000, 70000],
+        ] {
+            data.extend(group.iter().flat_map(|v: &u32| v.to_be_bytes()));
+        }
+        let lookup = |c| lookup_format_12(&data, c);
+        assert_eq!(lookup(0x40), None);
+        assert_eq!(lookup(0x41), Some(10));
+        assert_eq!(lookup(0x43), Some(12));
+        assert_eq!(lookup(0x44), None);
+        assert_eq!(lookup(0x1F601), Some(501));
+        assert_eq!(lookup(0x1F603), None);
+        // Glyph indices past 16 bits are invalid.
+        assert_eq!(lookup(0x20000), None);
+    }
+
+    #[test]
+    fn prefers_full_unicode_map() {
+        let mut cmap = words(&[0, 3, 3, 0]);
+        cmap.extend(28u32.to_be_bytes());
+        cmap.extend(words(&[3, 1]));
+        cmap.extend(28u32.to_be_bytes());
+        cmap.extend(words(&[3, 10]));
+        cmap.extend(52u32.to_be_bytes());
+        cmap.extend(words(&[4, 16, 0, 2, 2, 0, 0, 0xFFFF, 0, 0xFFFF, 1, 0]));
+        cmap.extend(words(&[12, 0, 0, 0, 0, 0, 0, 1]));
+        cmap.extend(
+            [0x1F600u32, 0x1F600, 2]
+                .iter()
+                .flat_map(|v| v.to_be_bytes()),
+        );
+        let cmap = find_character_map(&cmap).unwrap().unwrap();
+        assert_eq!((cmap.offset, cmap.format, cmap.symbol), (52, 12, false));
+    }
+
+    #[test]
+    fn maps_symbol_characters() {
+        let mut cmap = words(&[0, 1, 3, 0]);
+        cmap.extend(12u32.to_be_bytes());
+        cmap.extend(words(&[4, 32, 0, 4, 4, 1, 0]));
+        cmap.extend(words(&[
+            0xF041,
+            0xFFFF,
+            0,
+            0xF041,
+            0xFFFF,
+            2 - 0xF041,
+            1,
+            0,
+            0,
+        ]));
+        let data = font_data(3, &[(b"cmap", &cmap)]);
+        let font = Font::from_bytes(&data).unwrap();
+        assert_eq!(font.glyph_index('A'), Some(2));
+        assert_eq!(font.glyph_index('\u{F041}'), Some(2));
+        assert_eq!(font.glyph_index('B'), None);
+    }
+}
@@ -0,0 +1,1206 @@
+use crate::{FontError, OutlineBuilder, Reader, Shape, Vector2};
+
+/// The maximum nesting of subroutine calls allowed by the specification.
+const MAX_CALL_DEPTH: usize = 10;
+const MAX_STACK_CFF: usize = 48;
+const MAX_STACK_CFF2: usize = 513;
+
+const CFF: [u8; 4] = *b"CFF ";
+const CFF2: [u8; 4] = *b"CFF2";
+
+/// The SIDs of the glyph names in the Standard Encoding, which `seac` accents refer to.
+#[rustfmt::skip]
+const STANDARD_ENCODING: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32,
+    33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48,
+    49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64,
+    65, 66, 67, 68, 69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80,
+    81, 82, 83, 84, 85, 86, 87, 88, 89, 90, 91, 92, 93, 94, 95, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 96, 97, 98, 99, 100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110,
+    0, 111, 112, 113, 114, 0, 115, 116, 117, 118, 119, 120, 121, 122, 0, 123,
+    0, 124, 125, 126, 127, 128, 129, 130, 131, 0, 132, 133, 0, 134, 135, 136,
+    137, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 138, 0, 139, 0, 0, 0, 0, 140, 141, 142, 143, 0, 0, 0, 0,
+    0, 144, 0, 0, 0, 145, 0, 0, 146, 147, 148, 149, 0, 0, 0, 0,
+];
+
+/// An INDEX structure, i.e. an array of variable-sized objects.
+#[derive(Clone, Copy, Default)]
+struct Index<'a> {
+    count: usize,
+    off_size: usize,
+    offsets: &'a [u8],
+    data: &'a [u8],
+}
+
+impl<'a> Index<'a> {
+    fn read(reader: &mut Reader<'a>, version2: bool) -> Result<Index<'a>, FontError> {
+        let count = if version2 {
+            reader.u32()? as usize
+        } else {
+            reader.u16()? as usize
+        };
+        if count == 0 {
+            return Ok(Index::default());
+        }
+        let off_size = reader.u8()? as usize;
+        if !(1..=4).contains(&off_size) {
+            return Err(FontError::UnexpectedEnd);
+        }
+        let offsets = reader.bytes((count + 1) * off_size)?;
+        let mut index = Index {
+            count,
+            off_size,
+            offsets,
+            data: &[],
+        };
+        let len = index.offset(count).ok_or(FontError::UnexpectedEnd)?;
+        index.data = reader.bytes(len)?;
+        Ok(index)
+    }
+
+    /// Returns the offset of the object into the data, which the file stores 1-based.
+    fn offset(&self, i: usize) -> Option<usize> {
+        let bytes = self
+            .offsets
+            .get(i * self.off_size..(i + 1) * self.off_size)?;
+        let offset = bytes.iter().fold(0, |n, &b| n << 8 | b as usize);
+        offset.checked_sub(1)
+    }
+
+    fn get(&self, i: usize) -> Option<&'a [u8]> {
+        if i >= self.count {
+            return None;
+        }
+        self.data.get(self.offset(i)?..self.offset(i + 1)?)
+    }
+
+    /// The number added to subroutine numbers, which are stored biased to use short operands.
+    fn subr_bias(&self) -> i32 {
+        match self.count {
+            0..=1239 => 107,
+            1240..=33899 => 1131,
+            _ => 32768,
+        }
+    }
+}
+
+/// Parses a DICT, passing each operator and its operands to `f`. Operators with an escape byte
+/// are numbered from 1200.
+fn parse_dict(
+    data: &[u8],
+    mut f: impl FnMut(u16, &[f64]) -> Result<(), FontError>,
+) -> Result<(), FontError> {
+    let mut reader = Reader::new(data);
+    let mut operands = Vec::new();
+    while reader.pos() < data.len() {
+        let b0 = reader.u8()?;
+        match b0 {
+            // In CFF2 private dictionaries, blended values are only needed by operators we
+            // ignore, so the blend is left for the following operator to discard.
+            23 => {}
+            0..=27 => {
+                let op = if b0 == 12 {
+                    1200 + reader.u8()? as u16
+                } else {
+                    b0 as u16
+                };
+                f(op, &operands)?;
+                operands.clear();
+            }
+            28 => operands.push(reader.i16()? as f64),
+            29 => operands.push(reader.i32()? as f64),
+            30 => operands.push(parse_real(&mut reader)?),
+            32..=254 => operands.push(parse_short_int(&mut reader, b0)? as f64),
+            _ => return Err(FontError::UnexpectedEnd),
+        }
+    }
+    Ok(())
+}
+
+/// Parses the operands encoded in one to three bytes, starting with `b0`.
+fn parse_short_int(reader: &mut Reader, b0: u8) -> Result<i32, FontError> {
+    Ok(match b0 {
+        32..=246 => b0 as i32 - 139,
+        247..=250 => (b0 as i32 - 247) * 256 + reader.u8()? as i32 + 108,
+        251..=254 => -(b0 as i32 - 251) * 256 - reader.u8()? as i32 - 108,
+        _ => unreachable!(),
+    })
+}
+
+/// Parses a real number, which is encoded as a string of nibbles.
+fn parse_real(reader: &mut Reader) -> Result<f64, FontError> {
+    let mut text = String::new();
+    loop {
+        let byte = reader.u8()?;
+        for nibble in [byte >> 4, byte & 15] {
+            match nibble {
+                0..=9 => text.push((b'0' + nibble) as char),
+                0xA => text.push('.'),
+                0xB => text.push('E'),
+                0xC => text.push_str("E-"),
+                0xE => text.push('-'),
+                0xF => return Ok(text.parse().unwrap_or(0.0)),
+                _ => return Err(FontError::UnexpectedEnd),
+            }
+        }
+    }
+}
+
+/// The subroutines and variation settings of a Font DICT.
+#[derive(Clone, Copy, Default)]
+struct FontDict<'a> {
+    local_subrs: Index<'a>,
+    vsindex: u16,
+}
+
+impl<'a> FontDict<'a> {
+    /// Reads the Private DICT, at `offset` into the table.
+    fn read_private(
+        table: &'a [u8],
+        size: usize,
+        offset: usize,
+        version2: bool,
+    ) -> Result<Self, FontError> {
+        let private = table
+            .get(offset..offset.checked_add(size).ok_or(FontError::UnexpectedEnd)?)
+            .ok_or(FontError::UnexpectedEnd)?;
+        let mut subrs = None;
+        let mut vsindex = 0;
+        parse_dict(private, |op, operands| {
+            match (op, operands) {
+                (19, &[offset]) => subrs = Some(offset as usize),
+                (22, &[index]) => vsindex = index as u16,
+                _ => {}
+            }
+            Ok(())
+        })?;
+        let local_subrs = match subrs {
+            Some(subrs) => Index::read(&mut Reader::at(table, offset + subrs), version2)?,
+            None => Index::default(),
+        };
+        Ok(FontDict {
+            local_subrs,
+            vsindex,
+        })
+    }
+}
+
+/// Maps glyphs to Font DICTs in CID-keyed and CFF2 fonts.
+#[derive(Clone, Copy)]
+struct FdSelect<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> FdSelect<'a> {
+    fn font_dict(&self, glyph: u16) -> Result<usize, FontError> {
+        let mut reader = Reader::new(self.data);
+        let glyph = glyph as u32;
+        match reader.u8()? {
+            0 => {
+                reader.skip(glyph as usize);
+                Ok(reader.u8()? as usize)
+            }
+            format @ (3 | 4) => {
+                let wide = format == 4;
+                let read_first = |r: &mut Reader| {
+                    Ok::<_, FontError>(if wide { r.u32()? } else { r.u16()? as u32 })
+                };
+                let num_ranges = read_first(&mut reader)?;
+                let mut first = read_first(&mut reader)?;
+                for _ in 0..num_ranges {
+                    let fd = if wide {
+                        reader.u16()? as usize
+                    } else {
+                        reader.u8()? as usize
+                    };
+                    let next = read_first(&mut reader)?;
+                    if (first..next).contains(&glyph) {
+                        return Ok(fd);
+                    }
+                    first = next;
+                }
+                Err(FontError::UnexpectedEnd)
+            }
+            _ => Err(FontError::UnexpectedEnd),
+        }
+    }
+}
+
+/// The item variation store of a CFF2 table, which defines the regions that blended values
+/// depend on.
+#[derive(Clone, Copy)]
+pub(crate) struct ItemVariationStore<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ItemVariationStore<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Computes the scalar of each region referenced by the variation data at `outer`, for the
+    /// normalized variation coordinates `coords`. Missing coordinates are treated as 0.
+    pub(crate) fn region_scalars(&self, outer: u16, coords: &[f64]) -> Result<Vec<f64>, FontError> {
+        let mut reader = Reader::at(self.data, 2);
+        let region_list = reader.u32()? as usize;
+        let data_count = reader.u16()?;
+        if outer >= data_count {
+            return Err(FontError::UnexpectedEnd);
+        }
+        reader.skip(outer as usize * 4);
+        let mut data = Reader::at(self.data, reader.u32()? as usize + 4);
+        let region_index_count = data.u16()?;
+        let mut regions = Reader::at(self.data, region_list);
+        let axis_count = regions.u16()? as usize;
+        let region_count = regions.u16()?;
+        let regions_start = regions.pos();
+        (0..region_index_count)
+            .map(|_| {
+                let region = data.u16()?;
+                if region >= region_count {
+                    return Err(FontError::UnexpectedEnd);
+                }
+                let mut reader =
+                    Reader::at(self.data, regions_start + region as usize * axis_count * 6);
+                let mut scalar = 1.0;
+                for axis in 0..axis_count {
+                    let (start, peak, end) =
+                        (reader.f2dot14()?, reader.f2dot14()?, reader.f2dot14()?);
+                    let coord = coords.get(axis).copied().unwrap_or(0.0);
+                    scalar *= axis_scalar(coord, start, peak, end);
+                }
+                Ok(scalar)
+            })
+            .collect()
+    }
+}
+
+/// Computes how much a region applies along one axis, given the normalized coordinate.
+pub(crate) fn axis_scalar(coord: f64, start: f64, peak: f64, end: f64) -> f64 {
+    if peak == 0.0 || start > peak || peak > end || (start < 0.0 && end > 0.0) {
+        1.0
+    } else if coord < start || coord > end {
+        0.0
+    } else if coord == peak {
+        1.0
+    } else if coord < peak {
+        (coord - start) / (peak - start)
+    } else {
+        (end - coord) / (end - peak)
+    }
+}
+
+/// The parsed structure of a `CFF ` or `CFF2` table.
+pub(crate) struct Cff<'a> {
+    version2: bool,
+    global_subrs: Index<'a>,
+    char_strings: Index<'a>,
+    /// The offset of the charset, or the number of a predefined one, for `seac` lookups.
+    charset: usize,
+    table: &'a [u8],
+    font_dicts: Vec<FontDict<'a>>,
+    fd_select: Option<FdSelect<'a>>,
+    var_store: Option<ItemVariationStore<'a>>,
+}
+
+impl<'a> Cff<'a> {
+    pub(crate) fn parse(table: &'a [u8], version2: bool) -> Result<Cff<'a>, FontError> {
+        let tag = if version2 { CFF2 } else { CFF };
+        Self::parse_table(table, version2).map_err(|_| FontError::InvalidTable(tag))
+    }
+
+    fn parse_table(table: &'a [u8], version2: bool) -> Result<Cff<'a>, FontError> {
+        let mut reader = Reader::new(table);
+        let major = reader.u8()?;
+        reader.skip(1);
+        let header_size = reader.u8()? as usize;
+        if major != if version2 { 2 } else { 1 } {
+            return Err(FontError::UnexpectedEnd);
+        }
+        let top_dict = if version2 {
+            let top_dict_length = reader.u16()? as usize;
+            reader.seek(header_size);
+            reader.bytes(top_dict_length)?
+        } else {
+            reader.seek(header_size);
+            Index::read(&mut reader, false)?;
+            let top_dicts = Index::read(&mut reader, false)?;
+            Index::read(&mut reader, false)?;
+            top_dicts.get(0).ok_or(FontError::UnexpectedEnd)?
+        };
+        let global_subrs = Index::read(&mut reader, version2)?;
+
+        let mut char_strings = None;
+        let mut private = None;
+        let mut charset = 0;
+        let mut fd_array = None;
+        let mut fd_select = None;
+        let mut var_store = None;
+        parse_dict(top_dict, |op, operands| {
+            match (op, operands) {
+                (15, &[offset]) => charset = offset as usize,
+                (17, &[offset]) => char_strings = Some(offset as usize),
+                (18, &[size, offset]) => private = Some((size as usize, offset as usize)),
+                (24, &[offset]) => var_store = Some(offset as usize),
+                (1206, &[charstring_type]) if charstring_type != 2.0 => {
+                    return Err(FontError::UnexpectedEnd)
+                }
+                (1236, &[offset]) => fd_array = Some(offset as usize),
+                (1237, &[offset]) => fd_select = Some(offset as usize),
+                _ => {}
+            }
+            Ok(())
+        })?;
+        let char_strings = Index::read(
+            &mut Reader::at(table, char_strings.ok_or(FontError::UnexpectedEnd)?),
+            version2,
+        )?;
+        let font_dicts = match (fd_array, private) {
+            (Some(offset), _) => {
+                let fd_array = Index::read(&mut Reader::at(table, offset), version2)?;
+                (0..fd_array.count)
+                    .map(|i| {
+                        let mut private = None;
+                        parse_dict(fd_array.get(i).unwrap_or(&[]), |op, operands| {
+                            if let (18, &[size, offset]) = (op, operands) {
+                                private = Some((size as usize, offset as usize));
+                            }
+                            Ok(())
+                        })?;
+                        match private {
+                            Some((size, offset)) => {
+                                FontDict::read_private(table, size, offset, version2)
+                            }
+                            None => Ok(FontDict::default()),
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+            (None, Some((size, offset))) => {
+                vec![FontDict::read_private(table, size, offset, version2)?]
+            }
+            (None, None) => vec![FontDict::default()],
+        };
+        let fd_select = match fd_select {
+            Some(offset) => Some(FdSelect {
+                data: table.get(offset..).ok_or(FontError::UnexpectedEnd)?,
+            }),
+            None => None,
+        };
+        let var_store = match var_store {
+            // The store is preceded by its length.
+            Some(offset) => Some(ItemVariationStore::new(
+                table.get(offset + 2..).ok_or(FontError::UnexpectedEnd)?,
+            )),
+            None => None,
+        };
+        Ok(Cff {
+            version2,
+            global_subrs,
+            char_strings,
+            charset,
+            table,
+            font_dicts,
+            fd_select,
+            var_store,
+        })
+    }
+
+    fn tag(&self) -> [u8; 4] {
+        if self.version2 {
+            CFF2
+        } else {
+            CFF
+        }
+    }
+
+    /// Builds the glyph's outline, blending CFF2 values for the normalized variation
+    /// coordinates `coords`.
+    pub(crate) fn glyph_shape(&self, glyph: u16, coords: &[f64]) -> Result<Shape, FontError> {
+        let mut builder = OutlineBuilder::new();
+        self.append_glyph(&mut builder, glyph, Vector2::default(), coords, 0)
+            .map_err(|_| FontError::InvalidTable(self.tag()))?;
+        Ok(builder.finish())
+    }
+
+    fn append_glyph(
+        &self,
+        builder: &mut OutlineBuilder,
+        glyph: u16,
+        origin: Vector2,
+        coords: &[f64],
+        depth: usize,
+    ) -> Result<(), FontError> {
+        let char_string = self
+            .char_strings
+            .get(glyph as usize)
+            .ok_or(FontError::UnexpectedEnd)?;
+        let font_dict = match &self.fd_select {
+            Some(fd_select) => fd_select.font_dict(glyph)?,
+            None => 0,
+        };
+        let font_dict = self
+            .font_dicts
+            .get(font_dict)
+            .ok_or(FontError::UnexpectedEnd)?;
+        let mut interpreter = CharStringInterpreter {
+            cff: self,
+            local_subrs: font_dict.local_subrs,
+            coords,
+            scalars: None,
+            vsindex: font_dict.vsindex,
+            builder,
+            stack: Vec::new(),
+            point: origin,
+            num_stems: 0,
+            width_parsed: self.version2,
+            finished: false,
+            depth,
+        };
+        interpreter.run(char_string, 0)
+    }
+
+    /// Finds the glyph with the name of the Standard Encoding code `code`, using the charset.
+    fn standard_glyph(&self, code: f64) -> Result<u16, FontError> {
+        let sid = *STANDARD_ENCODING
+            .get(code as usize)
+            .ok_or(FontError::UnexpectedEnd)? as u16;
+        let num_glyphs = self.char_strings.count;
+        let glyph = match self.charset {
+            // The ISOAdobe charset maps glyphs to the SIDs with the same number.
+            0 => Some(sid as usize),
+            1 | 2 => None,
+            offset => {
+                let mut reader = Reader::at(self.table, offset);
+                let mut found = None;
+                match reader.u8()? {
+                    0 => {
+                        for glyph in 1..num_glyphs {
+                            if reader.u16()? == sid {
+                                found = Some(glyph);
+                                break;
+                            }
+                        }
+                    }
+                    format @ (1 | 2) => {
+                        let mut glyph = 1;
+                        while glyph < num_glyphs && found.is_none() {
+                            let first = reader.u16()? as usize;
+                            let left = if format == 1 {
+                                reader.u8()? as usize
+                            } else {
+                                reader.u16()? as usize
+                            };
+                            if (first..=first + left).contains(&(sid as usize)) {
+                                found = Some(glyph + sid as usize - first);
+                            }
+                            glyph += left + 1;
+                        }
+                    }
+                    _ => {}
+                }
+                found
+            }
+        };
+        glyph
+            .filter(|&glyph| glyph < num_glyphs)
+            .map(|glyph| glyph as u16)
+            .ok_or(FontError::UnexpectedEnd)
+    }
+}
+
+/// Executes a Type 2 charstring, adding its outline to the builder. Hints are skipped.
+struct CharStringInterpreter<'a, 'b> {
+    cff: &'b Cff<'a>,
+    local_subrs: Index<'a>,
+    coords: &'b [f64],
+    /// The region scalars for the current `vsindex`, computed on the first blend.
+    scalars: Option<Vec<f64>>,
+    vsindex: u16,
+    builder: &'b mut OutlineBuilder,
+    stack: Vec<f64>,
+    point: Vector2,
+    num_stems: usize,
+    /// Whether the optional advance width at the start of a CFF charstring has been seen.
+    width_parsed: bool,
+    finished: bool,
+    /// The nesting of `seac` accents.
+    depth: usize,
+}
+
+impl<'a, 'b> CharStringInterpreter<'a, 'b> {
+    fn push(&mut self, value: f64) -> Result<(), FontError> {
+        let limit = if self.cff.version2 {
+            MAX_STACK_CFF2
+        } else {
+            MAX_STACK_CFF
+        };
+        if self.stack.len() >= limit {
+            return Err(FontError::UnexpectedEnd);
+        }
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Removes the advance width from the start of the stack, if the first stack-clearing
+    /// operator was given one more argument than it takes.
+    fn parse_width(&mut self, extra: bool) {
+        if !self.width_parsed {
+            self.width_parsed = true;
+            if extra && !self.stack.is_empty() {
+                self.stack.remove(0);
+            }
+        }
+    }
+
+    fn stems(&mut self) {
+        self.parse_width(self.stack.len() % 2 == 1);
+        self.num_stems += self.stack.len() / 2;
+        self.stack.clear();
+    }
+
+    fn move_to(&mut self, d: Vector2) {
+        self.point += d;
+        self.builder.move_to(self.point);
+        self.stack.clear();
+    }
+
+    fn line_to(&mut self, d: Vector2) {
+        self.point += d;
+        self.builder.line_to(self.point);
+    }
+
+    fn curve_to(&mut self, d1: Vector2, d2: Vector2, d3: Vector2) {
+        let p1 = self.point + d1;
+        let p2 = p1 + d2;
+        self.point = p2 + d3;
+        self.builder.cubic_to(p1, p2, self.point);
+    }
+
+    /// Returns the arguments of a path operator, which takes one or more groups of `group`
+    /// arguments followed by `suffix` arguments.
+    fn args(&self, group: usize, suffix: usize) -> Result<Vec<f64>, FontError> {
+        let len = self.stack.len();
+        if len < group + suffix || !(len - suffix).is_multiple_of(group) {
+            return Err(FontError::UnexpectedEnd);
+        }
+        Ok(self.stack.clone())
+    }
+
+    /// Lines alternating between horizontal and vertical.
+    fn alternating_lines(&mut self, mut horizontal: bool) -> Result<(), FontError> {
+        if self.stack.is_empty() {
+            return Err(FontError::UnexpectedEnd);
+        }
+        for d in std::mem::take(&mut self.stack) {
+            self.line_to(if horizontal {
+                Vector2::new(d, 0.0)
+            } else {
+                Vector2::new(0.0, d)
+            });
+            horizontal = !horizontal;
+        }
+        Ok(())
+    }
+
+    /// Curves alternating between starting horizontally and vertically, where the last one may
+    /// have a final coordinate that is not axis-aligned.
+    fn alternating_curves(&mut self, mut horizontal: bool) -> Result<(), FontError> {
+        let args = std::mem::take(&mut self.stack);
+        if args.len() < 4 || !matches!(args.len() % 4, 0 | 1) {
+            return Err(FontError::UnexpectedEnd);
+        }
+        let mut i = 0;
+        while i + 4 <= args.len() {
+            let last = if args.len() - i == 5 {
+                args[i + 4]
+            } else {
+                0.0
+            };
+            let a = &args[i..i + 4];
+            if horizontal {
+                self.curve_to(
+                    Vector2::new(a[0], 0.0),
+                    Vector2::new(a[1], a[2]),
+                    Vector2::new(last, a[3]),
+                );
+            } else {
+                self.curve_to(
+                    Vector2::new(0.0, a[0]),
+                    Vector2::new(a[1], a[2]),
+                    Vector2::new(a[3], last),
+                );
+            }
+            horizontal = !horizontal;
+            i += 4;
+        }
+        Ok(())
+    }
+
+    fn blend(&mut self) -> Result<(), FontError> {
+        let n = self.stack.pop().ok_or(FontError::UnexpectedEnd)? as usize;
+        if self.scalars.is_none() {
+            let scalars = match &self.cff.var_store {
+                Some(store) => store.region_scalars(self.vsindex, self.coords)?,
+                None => Vec::new(),
+            };
+            self.scalars = Some(scalars);
+        }
+        let scalars = self.scalars.as_ref().unwrap();
+        let k = scalars.len();
+        let total = n.checked_mul(k + 1).ok_or(FontError::UnexpectedEnd)?;
+        if self.stack.len() < total {
+            return Err(FontError::UnexpectedEnd);
+        }
+        let base = self.stack.len() - total;
+        for i in 0..n {
+            let deltas = &self.stack[base + n + i * k..base + n + (i + 1) * k];
+            let delta: f64 = deltas.iter().zip(scalars).map(|(d, s)| d * s).sum();
+            self.stack[base + i] += delta;
+        }
+        self.stack.truncate(base + n);
+        Ok(())
+    }
+
+    fn call_subr(&mut self, global: bool, call_depth: usize) -> Result<(), FontError> {
+        let subrs = if global {
+            self.cff.global_subrs
+        } else {
+            self.local_subrs
+        };
+        let index = self.stack.pop().ok_or(FontError::UnexpectedEnd)? as i32 + subrs.subr_bias();
+        let subr = usize::try_from(index)
+            .ok()
+            .and_then(|index| subrs.get(index))
+            .ok_or(FontError::UnexpectedEnd)?;
+        self.run(subr, call_depth + 1)
+    }
+
+    /// Draws an accented character from two glyphs of the Standard Encoding, with the accent
+    /// offset by `(adx, ady)`.
+    fn seac(&mut self, adx: f64, ady: f64, base: f64, accent: f64) -> Result<(), FontError> {
+        if self.depth > 0 {
+            return Err(FontError::UnexpectedEnd);
+        }
+        let base = self.cff.standard_glyph(base)?;
+        let accent = self.cff.standard_glyph(accent)?;
+        let cff = self.cff;
+        cff.append_glyph(self.builder, base, Vector2::default(), self.coords, 1)?;
+        cff.append_glyph(self.builder, accent, Vector2::new(adx, ady), self.coords, 1)
+    }
+
+    fn run(&mut self, data: &[u8], call_depth: usize) -> Result<(), FontError> {
+        if call_depth > MAX_CALL_DEPTH {
+            return Err(FontError::UnexpectedEnd);
+        }
+        let mut reader = Reader::new(data);
+        while reader.pos() < data.len() && !self.finished {
+            let b0 = reader.u8()?;
+            match b0 {
+                // hstem, vstem, hstemhm, vstemhm
+                1 | 3 | 18 | 23 => self.stems(),
+                // vmoveto
+                4 => {
+                    self.parse_width(self.stack.len() > 1);
+                    let dy = *self.stack.last().ok_or(FontError::UnexpectedEnd)?;
+                    self.move_to(Vector2::new(0.0, dy));
+                }
+                // rlineto
+                5 => {
+                    for d in self.args(2, 0)?.chunks_exact(2) {
+                        self.line_to(Vector2::new(d[0], d[1]));
+                    }
+                    self.stack.clear();
+                }
+                // hlineto, vlineto
+                6 | 7 => self.alternating_lines(b0 == 6)?,
+                // rrcurveto
+                8 => {
+                    for d in self.args(6, 0)?.chunks_exact(6) {
+                        self.curve_to(
+                            Vector2::new(d[0], d[1]),
+                            Vector2::new(d[2], d[3]),
+                            Vector2::new(d[4], d[5]),
+                        );
+                    }
+                    self.stack.clear();
+                }
+                // callsubr, callgsubr
+                10 | 29 => self.call_subr(b0 == 29, call_depth)?,
+                // return
+                11 if !self.cff.version2 => return Ok(()),
+                // endchar
+                14 if !self.cff.version2 => {
+                    self.parse_width(matches!(self.stack.len(), 1 | 5));
+                    if let &[adx, ady, base, accent] = &self.stack[..] {
+                        self.seac(adx, ady, base, accent)?;
+                    }
+                    self.stack.clear();
+                    self.finished = true;
+                }
+                // vsindex
+                15 if self.cff.version2 => {
+                    self.vsindex = self.stack.pop().ok_or(FontError::UnexpectedEnd)? as u16;
+                    self.scalars = None;
+                    self.stack.clear();
+                }
+                // blend
+                16 if self.cff.version2 => self.blend()?,
+                // hintmask, cntrmask
+                19 | 20 => {
+                    self.stems();
+                    reader.skip(self.num_stems.div_ceil(8));
+                }
+                // rmoveto
+                21 => {
+                    self.parse_width(self.stack.len() > 2);
+                    match self.stack[..] {
+                        [.., dx, dy] => self.move_to(Vector2::new(dx, dy)),
+                        _ => return Err(FontError::UnexpectedEnd),
+                    }
+                }
+                // hmoveto
+                22 => {
+                    self.parse_width(self.stack.len() > 1);
+                    let dx = *self.stack.last().ok_or(FontError::UnexpectedEnd)?;
+                    self.move_to(Vector2::new(dx, 0.0));
+                }
+                // rcurveline
+                24 => {
+                    let args = self.args(6, 2)?;
+                    let (curves, line) = args.split_at(args.len() - 2);
+                    for d in curves.chunks_exact(6) {
+                        self.curve_to(
+                            Vector2::new(d[0], d[1]),
+                            Vector2::new(d[2], d[3]),
+                            Vector2::new(d[4], d[5]),
+                        );
+                    }
+                    self.line_to(Vector2::new(line[0], line[1]));
+                    self.stack.clear();
+                }
+                // rlinecurve
+                25 => {
+                    let args = self.args(2, 6)?;
+                    let (lines, d) = args.split_at(args.len() - 6);
+                    for l in lines.chunks_exact(2) {
+                        self.line_to(Vector2::new(l[0], l[1]));
+                    }
+                    self.curve_to(
+                        Vector2::new(d[0], d[1]),
+                        Vector2::new(d[2], d[3]),
+                        Vector2::new(d[4], d[5]),
+                    );
+                    self.stack.clear();
+                }
+                // vvcurveto, hhcurveto
+                26 | 27 => {
+                    let mut args = std::mem::take(&mut self.stack);
+                    let first = if args.len() % 4 == 1 {
+                        args.remove(0)
+                    } else {
+                        0.0
+                    };
+                    if args.is_empty() || !args.len().is_multiple_of(4) {
+                        return Err(FontError::UnexpectedEnd);
+                    }
+                    for (i, a) in args.chunks_exact(4).enumerate() {
+                        let offset = if i == 0 { first } else { 0.0 };
+                        if b0 == 26 {
+                            self.curve_to(
+                                Vector2::new(offset, a[0]),
+                                Vector2::new(a[1], a[2]),
+                                Vector2::new(0.0, a[3]),
+                            );
+                        } else {
+                            self.curve_to(
+                                Vector2::new(a[0], offset),
+                                Vector2::new(a[1], a[2]),
+                                Vector2::new(a[3], 0.0),
+                            );
+                        }
+                    }
+                }
+                28 => self.push(reader.i16()? as f64)?,
+                // vhcurveto, hvcurveto
+                30 | 31 => self.alternating_curves(b0 == 31)?,
+                32..=254 => {
+                    let value = parse_short_int(&mut reader, b0)?;
+                    self.push(value as f64)?;
+                }
+                255 => self.push(reader.fixed()?)?,
+                12 => {
+                    let op = reader.u8()?;
+                    self.escape(op)?;
+                }
+                _ => return Err(FontError::UnexpectedEnd),
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes an escaped operator, of which only the flex curves and the deprecated
+    /// `dotsection` are supported.
+    fn escape(&mut self, op: u8) -> Result<(), FontError> {
+        let args = std::mem::take(&mut self.stack);
+        let v = Vector2::new;
+        match (op, &args[..]) {
+            // dotsection
+            (0, _) => {}
+            // hflex
+            (34, &[dx1, dx2, dy2, dx3, dx4, dx5, dx6]) => {
+                self.curve_to(v(dx1, 0.0), v(dx2, dy2), v(dx3, 0.0));
+                self.curve_to(v(dx4, 0.0), v(dx5, -dy2), v(dx6, 0.0));
+            }
+            // flex
+            (35, &[dx1, dy1, dx2, dy2, dx3, dy3, dx4, dy4, dx5, dy5, dx6, dy6, _fd]) => {
+                self.curve_to(v(dx1, dy1), v(dx2, dy2), v(dx3, dy3));
+                self.curve_to(v(dx4, dy4), v(dx5, dy5), v(dx6, dy6));
+            }
+            // hflex1
+            (36, &[dx1, dy1, dx2, dy2, dx3, dx4, dx5, dy5, dx6]) => {
+                self.curve_to(v(dx1, dy1), v(dx2, dy2), v(dx3, 0.0));
+                self.curve_to(v(dx4, 0.0), v(dx5, dy5), v(dx6, -(dy1 + dy2 + dy5)));
+            }
+            // flex1
+            (37, &[dx1, dy1, dx2, dy2, dx3, dy3, dx4, dy4, dx5, dy5, d6]) => {
+                let dx = dx1 + dx2 + dx3 + dx4 + dx5;
+                let dy = dy1 + dy2 + dy3 + dy4 + dy5;
+                let last = if dx.abs() > dy.abs() {
+                    v(d6, -dy)
+                } else {
+                    v(-dx, d6)
+                };
+                self.curve_to(v(dx1, dy1), v(dx2, dy2), v(dx3, dy3));
+                self.curve_to(v(dx4, dy4), v(dx5, dy5), last);
+            }
+            _ => return Err(FontError::UnexpectedEnd),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicSegment, LinearSegment, Segment};
+
+    fn v(x: f64, y: f64) -> Vector2 {
+        Vector2::new(x, y)
+    }
+
+    /// Assembles a charstring from operator names, integer operands, and raw bytes written as
+    /// `#` followed by two hex digits.
+    fn char_string(source: &str) -> Vec<u8> {
+        let mut data = Vec::new();
+        for token in source.split_whitespace() {
+            let op: &[u8] = match token {
+                "hstem" => &[1],
+                "rlineto" => &[5],
+                "hlineto" => &[6],
+                "vlineto" => &[7],
+                "rrcurveto" => &[8],
+                "callsubr" => &[10],
+                "return" => &[11],
+                "endchar" => &[14],
+                "blend" => &[16],
+                "hintmask" => &[19],
+                "rmoveto" => &[21],
+                "callgsubr" => &[29],
+                "hflex" => &[12, 34],
+                "flex1" => &[12, 37],
+                _ => {
+                    if let Some(hex) = token.strip_prefix('#') {
+                        data.push(u8::from_str_radix(hex, 16).unwrap());
+                    } else {
+                        data.push(28);
+                        data.extend(token.parse::<i16>().unwrap().to_be_bytes());
+                    }
+                    continue;
+                }
+            };
+            data.extend(op);
+        }
+        data
+    }
+
+    fn index(items: &[Vec<u8>], version2: bool) -> Vec<u8> {
+        let mut data = if version2 {
+            (items.len() as u32).to_be_bytes().to_vec()
+        } else {
+            (items.len() as u16).to_be_bytes().to_vec()
+        };
+        if items.is_empty() {
+            return data;
+        }
+        data.push(4);
+        let mut offset = 1u32;
+        data.extend(offset.to_be_bytes());
+        for item in items {
+            offset += item.len() as u32;
+            data.extend(offset.to_be_bytes());
+        }
+        data.extend(items.concat());
+        data
+    }
+
+    /// The `INDEX` data of the global subroutines, local subroutines and charstrings.
+    struct Indices {
+        global_subrs: Vec<u8>,
+        local_subrs: Vec<u8>,
+        char_strings: Vec<u8>,
+    }
+
+    impl Indices {
+        fn new(global_subrs: &[&str], local_subrs: &[&str], char_strings: &[Vec<u8>]) -> Self {
+            let subrs = |sources: &[&str]| {
+                let items: Vec<Vec<u8>> = sources.iter().map(|s| char_string(s)).collect();
+                index(&items, false)
+            };
+            Self {
+                global_subrs: subrs(global_subrs),
+                local_subrs: subrs(local_subrs),
+                char_strings: index(char_strings, false),
+            }
+        }
+
+        fn cff(&self) -> Cff<'_> {
+            let read = |data| Index::read(&mut Reader::new(data), false).unwrap();
+            Cff {
+                version2: false,
+                global_subrs: read(&self.global_subrs),
+                char_strings: read(&self.char_strings),
+                charset: 0,
+                table: &[],
+                font_dicts: vec![FontDict {
+                    local_subrs: read(&self.local_subrs),
+                    vsindex: 0,
+                }],
+                fd_select: None,
+                var_store: None,
+            }
+        }
+    }
+
+    /// Runs the charstring and returns the points of the edges of each contour.
+    fn run(cff: &Cff, source: &str, coords: &[f64]) -> Result<Vec<Vec<Vec<Vector2>>>, FontError> {
+        let mut builder = OutlineBuilder::new();
+        let mut interpreter = CharStringInterpreter {
+            cff,
+            local_subrs: cff.font_dicts[0].local_subrs,
+            coords,
+            scalars: None,
+            vsindex: 0,
+            builder: &mut builder,
+            stack: Vec::new(),
+            point: Vector2::default(),
+            num_stems: 0,
+            width_parsed: cff.version2,
+            finished: false,
+            depth: 0,
+        };
+        interpreter.run(&char_string(source), 0)?;
+        Ok(edges(&builder.finish()))
+    }
+
+    fn edges(shape: &Shape) -> Vec<Vec<Vec<Vector2>>> {
+        shape
+            .contours
+            .iter()
+            .map(|contour| {
+                contour
+                    .edges
+                    .iter()
+                    .map(|edge| match edge.segment {
+                        Segment::Linear(LinearSegment(p0, p1)) => vec![p0, p1],
+                        Segment::Cubic(CubicSegment(p0, p1, p2, p3)) => vec![p0, p1, p2, p3],
+                        Segment::Quadratic(_) => unreachable!(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn draws_relative_paths() {
+        let indices = Indices::new(&[], &[], &[]);
+        let cff = indices.cff();
+        let contours = run(
+            &cff,
+            "10 20 rmoveto 30 0 0 40 rlineto 0 10 10 10 10 0 rrcurveto \
+             100 100 rmoveto 10 hlineto endchar 50 50 rlineto",
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            contours,
+            [
+                vec![
+                    vec![v(10.0, 20.0), v(40.0, 20.0)],
+                    vec![v(40.0, 20.0), v(40.0, 60.0)],
+                    vec![v(40.0, 60.0), v(40.0, 70.0), v(50.0, 80.0), v(60.0, 80.0)],
+                    vec![v(60.0, 80.0), v(10.0, 20.0)],
+                ],
+                vec![
+                    vec![v(160.0, 180.0), v(170.0, 180.0)],
+                    vec![v(170.0, 180.0), v(160.0, 180.0)],
+                ],
+            ]
+        );
+        assert!(run(&cff, "10 20 30 rlineto", &[]).is_err());
+        assert!(run(&cff, "1 2 3 4 5 rrcurveto", &[]).is_err());
+    }
+
+    #[test]
+    fn strips_advance_width() {
+        // The accent is drawn from the glyphs of the Standard Encoding codes 65 and 194,
+        // which the ISOAdobe charset maps to glyphs 34 and 125.
+        let mut char_strings = vec![char_string("endchar"); 126];
+        char_strings[34] = char_string("0 0 rmoveto 10 hlineto 10 vlineto endchar");
+        char_strings[125] = char_string("500 0 0 rmoveto 5 5 rlineto endchar");
+        char_strings[1] = char_string("50 100 200 65 194 endchar");
+        char_strings[2] = char_string("100 200 65 194 endchar");
+        char_strings[3] = char_string("50 10 20 rmoveto 10 hlineto 50 endchar");
+        let indices = Indices::new(&[], &[], &char_strings);
+        let cff = indices.cff();
+        let accented = [
+            vec![
+                vec![v(0.0, 0.0), v(10.0, 0.0)],
+                vec![v(10.0, 0.0), v(10.0, 10.0)],
+                vec![v(10.0, 10.0), v(0.0, 0.0)],
+            ],
+            vec![
+                vec![v(100.0, 200.0), v(105.0, 205.0)],
+                vec![v(105.0, 205.0), v(100.0, 200.0)],
+            ],
+        ];
+        assert_eq!(edges(&cff.glyph_shape(1, &[]).unwrap()), accented);
+        assert_eq!(edges(&cff.glyph_shape(2, &[]).unwrap()), accented);
+        assert_eq!(
+            edges(&cff.glyph_shape(3, &[]).unwrap()),
+            [[
+                vec![v(10.0, 20.0), v(20.0, 20.0)],
+                vec![v(20.0, 20.0), v(10.0, 20.0)],
+            ]]
+        );
+    }
+
+    #[test]
+    fn skips_hint_masks() {
+        // 2 stems plus 7 implied vertical stems need 2 mask bytes, which would otherwise be
+        // read as rmoveto and rlineto.
+        let indices = Indices::new(&[], &[], &[]);
+        let cff = indices.cff();
+        let contours = run(
+            &cff,
+            "50 0 10 20 10 hstem 0 10 20 10 30 10 40 10 50 10 60 10 70 10 hintmask #15 #05 \
+             10 20 rmoveto hintmask #15 #05 30 0 rlineto endchar",
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            contours,
+            [[
+                vec![v(10.0, 20.0), v(40.0, 20.0)],
+                vec![v(40.0, 20.0), v(10.0, 20.0)],
+            ]]
+        );
+    }
+
+    #[test]
+    fn draws_flex_curves() {
+        let indices = Indices::new(&[], &[], &[]);
+        let cff = indices.cff();
+        let contours = run(&cff, "0 0 rmoveto 10 20 30 40 50 60 70 hflex", &[]).unwrap();
+        assert_eq!(
+            contours[0][0][1..],
+            [v(10.0, 0.0), v(30.0, 30.0), v(70.0, 30.0)]
+        );
+        assert_eq!(
+            contours[0][1][1..],
+            [v(120.0, 30.0), v(180.0, 0.0), v(250.0, 0.0)]
+        );
+        // The last coordinate of flex1 is along the axis of the larger total movement.
+        let contours = run(
+            &cff,
+            "0 0 rmoveto 10 10 10 10 10 10 10 -10 10 -10 20 flex1",
+            &[],
+        )
+        .unwrap();
+        assert_eq!(contours[0][0][3], v(30.0, 30.0));
+        assert_eq!(contours[0][1][3], v(70.0, 0.0));
+        let contours = run(
+            &cff,
+            "0 0 rmoveto 10 10 10 10 10 10 -10 10 -10 10 20 flex1",
+            &[],
+        )
+        .unwrap();
+        assert_eq!(contours[0][1][3], v(0.0, 70.0));
+        assert!(run(&cff, "0 0 rmoveto 1 2 3 hflex", &[]).is_err());
+    }
+
+    #[test]
+    fn calls_biased_subroutines() {
+        let indices = Indices::new(
+            &["0 -30 rlineto return"],
+            &["30 0 rlineto return", "0 30 rlineto -107 callgsubr return"],
+            &[],
+        );
+        let cff = indices.cff();
+        let contours = run(&cff, "0 0 rmoveto -107 callsubr -106 callsubr endchar", &[]).unwrap();
+        assert_eq!(
+            contours,
+            [[
+                vec![v(0.0, 0.0), v(30.0, 0.0)],
+                vec![v(30.0, 0.0), v(30.0, 30.0)],
+                vec![v(30.0, 30.0), v(30.0, 0.0)],
+                vec![v(30.0, 0.0), v(0.0, 0.0)],
+            ]]
+        );
+        assert!(run(&cff, "-108 callsubr", &[]).is_err());
+        assert!(run(&cff, "-105 callsubr", &[]).is_err());
+        let bias = |count| {
+            Index {
+                count,
+                ..Index::default()
+            }
+            .subr_bias()
+        };
+        assert_eq!(
+            [bias(1239), bias(1240), bias(33899), bias(33900)],
+            [107, 1131, 1131, 32768]
+        );
+    }
+
+    #[test]
+    fn blends_values() {
+        // One region peaking at 1 on the only axis.
+        let mut store = vec![0, 1, 0, 0, 0, 12, 0, 1, 0, 0, 0, 22];
+        store.extend([0, 1, 0, 1, 0, 0, 0x40, 0, 0x40, 0]);
+        store.extend([0, 0, 0, 0, 0, 1, 0, 0]);
+        let global_subrs = index(&[], true);
+        let cff = Cff {
+            version2: true,
+            global_subrs: Index::read(&mut Reader::new(&global_subrs), true).unwrap(),
+            char_strings: Index::default(),
+            charset: 0,
+            table: &[],
+            font_dicts: vec![FontDict::default()],
+            fd_select: None,
+            var_store: Some(ItemVariationStore::new(&store)),
+        };
+        let source = "0 0 rmoveto 100 10 50 -10 2 blend rlineto";
+        let end = |coords: &[f64]| run(&cff, source, coords).unwrap()[0][0][1];
+        assert_eq!(end(&[]), v(100.0, 10.0));
+        assert_eq!(end(&[0.5]), v(125.0, 5.0));
+        assert_eq!(end(&[1.0]), v(150.0, 0.0));
+        assert_eq!(end(&[-1.0]), v(100.0, 10.0));
+        assert!(run(&cff, "0 0 rmoveto 100 50 2 blend", &[1.0]).is_err());
+    }
+}
@@ -0,0 +1,819 @@
+use crate::{Font, FontError, Reader};
+use std::collections::BTreeMap;
+
+/// The `fsSelection` bit of the `OS/2` table that says the typographic metrics should be used
+/// for line layout.
+const USE_TYPO_METRICS: u16 = 0x0080;
+
+const X_ADVANCE: u16 = 0x0004;
+
+const PAIR_ADJUSTMENT: u16 = 2;
+const EXTENSION: u16 = 9;
+
+const GPOS: [u8; 4] = *b"GPOS";
+const KERN: [u8; 4] = *b"kern";
+
+/// The vertical metrics of a font, in font units, with the y-axis pointing up.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FontMetrics {
+    pub units_per_em: u16,
+    /// The distance from the baseline to the top of the line.
+    pub ascender: i16,
+    /// The distance from the baseline to the bottom of the line, which is usually negative.
+    pub descender: i16,
+    /// The extra space between the bottom of a line and the top of the next one.
+    pub line_gap: i16,
+}
+
+impl FontMetrics {
+    /// The distance between the baselines of consecutive lines.
+    #[inline]
+    pub fn line_height(&self) -> i32 {
+        self.ascender as i32 - self.descender as i32 + self.line_gap as i32
+    }
+}
+
+/// The adjustment of the advance between two glyphs, in font units.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct KerningPair {
+    pub left: u16,
+    pub right: u16,
+    pub advance: i16,
+}
+
+/// Finds the index of the glyph in a coverage table.
+fn coverage_index(coverage: &[u8], glyph: u16) -> Result<Option<usize>, FontError> {
+    let mut reader = Reader::new(coverage);
+    let format = reader.u16()?;
+    let count = reader.u16()? as usize;
+    match format {
+        1 => {
+            for i in 0..count {
+                if reader.u16()? == glyph {
+                    return Ok(Some(i));
+                }
+            }
+        }
+        2 => {
+            for _ in 0..count {
+                let (start, end, index) = (reader.u16()?, reader.u16()?, reader.u16()?);
+                if (start..=end).contains(&glyph) {
+                    return Ok(Some(index as usize + (glyph - start) as usize));
+                }
+            }
+        }
+        _ => return Err(FontError::InvalidTable(GPOS)),
+    }
+    Ok(None)
+}
+
+/// Lists the glyphs of a coverage table, in the order of their coverage indices.
+fn coverage_glyphs(coverage: &[u8]) -> Result<Vec<u16>, FontError> {
+    let mut reader = Reader::new(coverage);
+    let format = reader.u16()?;
+    let count = reader.u16()? as usize;
+    let mut glyphs = Vec::new();
+    match format {
+        1 => {
+            for _ in 0..count {
+                glyphs.push(reader.u16()?);
+            }
+        }
+        2 => {
+            for _ in 0..count {
+                let (start, end, index) = (reader.u16()?, reader.u16()?, reader.u16()? as usize);
+                let range = start..=end;
+                if glyphs.len() < index + range.len() {
+                    glyphs.resize(index + range.len(), 0);
+                }
+                for (i, glyph) in range.enumerate() {
+                    glyphs[index + i] = glyph;
+                }
+            }
+        }
+        _ => return Err(FontError::InvalidTable(GPOS)),
+    }
+    Ok(glyphs)
+}
+
+/// Finds the class of the glyph in a class definition table. Glyphs that are not listed are in
+/// class 0.
+fn glyph_class(class_def: &[u8], glyph: u16) -> Result<u16, FontError> {
+    let mut reader = Reader::new(class_def);
+    match reader.u16()? {
+        1 => {
+            let start = reader.u16()? as usize;
+            let count = reader.u16()? as usize;
+            let glyph = glyph as usize;
+            if glyph >= start && glyph - start < count {
+                reader.skip((glyph - start) * 2);
+                return reader.u16();
+            }
+        }
+        2 => {
+            let count = reader.u16()?;
+            for _ in 0..count {
+                let (start, end, class) = (reader.u16()?, reader.u16()?, reader.u16()?);
+                if (start..=end).contains(&glyph) {
+                    return Ok(class);
+                }
+            }
+        }
+        _ => return Err(FontError::InvalidTable(GPOS)),
+    }
+    Ok(0)
+}
+
+/// The size of a value record with the given format.
+#[inline]
+fn value_record_size(format: u16) -> usize {
+    format.count_ones() as usize * 2
+}
+
+/// Reads the horizontal advance adjustment of a value record, ignoring the other values.
+fn value_record_advance(reader: &Reader, format: u16) -> Result<i16, FontError> {
+    if format & X_ADVANCE == 0 {
+        return Ok(0);
+    }
+    let mut reader = reader.clone();
+    reader.skip(value_record_size(format & (X_ADVANCE - 1)));
+    reader.i16()
+}
+
+/// A pair adjustment subtable of the `GPOS` table, which is either a list of glyph pairs
+/// (format 1) or a matrix of glyph classes (format 2).
+struct PairAdjustment<'a> {
+    data: &'a [u8],
+    format: u16,
+    coverage: &'a [u8],
+    value_format1: u16,
+    value_format2: u16,
+    /// The class definitions and class counts of a format 2 subtable.
+    class_def1: &'a [u8],
+    class_def2: &'a [u8],
+    class1_count: u16,
+    class2_count: u16,
+}
+
+impl<'a> PairAdjustment<'a> {
+    fn new(data: &'a [u8]) -> Result<Self, FontError> {
+        let mut reader = Reader::new(data);
+        let format = reader.u16()?;
+        let coverage = data
+            .get(reader.u16()? as usize..)
+            .ok_or(FontError::UnexpectedEnd)?;
+        let value_format1 = reader.u16()?;
+        let value_format2 = reader.u16()?;
+        let mut subtable = Self {
+            data,
+            format,
+            coverage,
+            value_format1,
+            value_format2,
+            class_def1: &[],
+            class_def2: &[],
+            class1_count: 0,
+            class2_count: 0,
+        };
+        if format == 2 {
+            let class_def1 = data.get(reader.u16()? as usize..);
+            let class_def2 = data.get(reader.u16()? as usize..);
+            subtable.class_def1 = class_def1.ok_or(FontError::UnexpectedEnd)?;
+            subtable.class_def2 = class_def2.ok_or(FontError::UnexpectedEnd)?;
+            subtable.class1_count = reader.u16()?;
+            subtable.class2_count = reader.u16()?;
+        }
+        Ok(subtable)
+    }
+
+    #[inline]
+    fn pair_size(&self) -> usize {
+        value_record_size(self.value_format1) + value_record_size(self.value_format2)
+    }
+
+    /// Returns a reader at the first pair of a glyph's pair set in a format 1 subtable, along
+    /// with the number of pairs.
+    fn pair_set(&self, coverage_index: usize) -> Result<(Reader<'a>, usize), FontError> {
+        let mut reader = Reader::at(self.data, 10 + coverage_index * 2);
+        let mut pair_set = Reader::at(self.data, reader.u16()? as usize);
+        let len = pair_set.u16()? as usize;
+        Ok((pair_set, len))
+    }
+
+    fn class_advance(&self, class1: u16, class2: u16) -> Result<i16, FontError> {
+        let index = class1 as usize * self.class2_count as usize + class2 as usize;
+        let reader = Reader::at(self.data, 16 + index * self.pair_size());
+        value_record_advance(&reader, self.value_format1)
+    }
+
+    fn advance(&self, left: u16, right: u16) -> Result<Option<i16>, FontError> {
+        let Some(coverage_index) = coverage_index(self.coverage, left)? else {
+            return Ok(None);
+        };
+        match self.format {
+            1 => {
+                let (mut reader, len) = self.pair_set(coverage_index)?;
+                for _ in 0..len {
+                    let second = reader.u16()?;
+                    if second == right {
+                        return value_record_advance(&reader, self.value_format1).map(Some);
+                    }
+                    reader.skip(self.pair_size());
+                }
+                Ok(None)
+            }
+            2 => {
+                let class1 = glyph_class(self.class_def1, left)?;
+                let class2 = glyph_class(self.class_def2, right)?;
+                if class1 >= self.class1_count || class2 >= self.class2_count {
+                    return Ok(None);
+                }
+                self.class_advance(class1, class2).map(Some)
+            }
+            _ => Err(FontError::InvalidTable(GPOS)),
+        }
+    }
+
+    /// Adds the subtable's adjustments to `pairs`, unless they are already there. Zero
+    /// adjustments are added too, so that later subtables can't override them.
+    fn collect(
+        &self,
+        num_glyphs: u16,
+        pairs: &mut BTreeMap<(u16, u16), i16>,
+    ) -> Result<(), FontError> {
+        let lefts = coverage_glyphs(self.coverage)?;
+        match self.format {
+            1 => {
+                for (coverage_index, &left) in lefts.iter().enumerate() {
+                    let (mut reader, len) = self.pair_set(coverage_index)?;
+                    for _ in 0..len {
+                        let right = reader.u16()?;
+                        let advance = value_record_advance(&reader, self.value_format1)?;
+                        pairs.entry((left, right)).or_insert(advance);
+                        reader.skip(self.pair_size());
+                    }
+                }
+            }
+            2 => {
+                let mut rights = vec![Vec::new(); self.class2_count as usize];
+                for glyph in 0..num_glyphs {
+                    if let Some(class) =
+                        rights.get_mut(glyph_class(self.class_def2, glyph)? as usize)
+                    {
+                        class.push(glyph);
+                    }
+                }
+                for left in lefts {
+                    let class1 = glyph_class(self.class_def1, left)?;
+                    if class1 >= self.class1_count {
+                        continue;
+                    }
+                    for (class2, rights) in rights.iter().enumerate() {
+                        let advance = self.class_advance(class1, class2 as u16)?;
+                        for &right in rights {
+                            pairs.entry((left, right)).or_insert(advance);
+                        }
+                    }
+                }
+            }
+            _ => return Err(FontError::InvalidTable(GPOS)),
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Font<'a> {
+    /// Returns the vertical metrics of the font. These come from the `hhea` table, unless the
+    /// `OS/2` table asks for its typographic metrics to be used, or there is no `hhea` table.
+    pub fn metrics(&self) -> Result<FontMetrics, FontError> {
+        let os2 = self.table(b"OS/2").filter(|os2| os2.len() >= 74);
+        let use_typo_metrics = os2.is_some_and(|os2| {
+            let fs_selection = Reader::at(os2, 62).u16();
+            fs_selection.is_ok_and(|flags| flags & USE_TYPO_METRICS != 0)
+        });
+        let mut reader = match (self.table(b"hhea"), os2) {
+            (Some(hhea), _) if !use_typo_metrics => Reader::at(hhea, 4),
+            (_, Some(os2)) => Reader::at(os2, 68),
+            _ => return Err(FontError::MissingTable(*b"hhea")),
+        };
+        Ok(FontMetrics {
+            units_per_em: self.units_per_em(),
+            ascender: reader.i16()?,
+            descender: reader.i16()?,
+            line_gap: reader.i16()?,
+        })
+    }
+
+    /// Returns the pair adjustment subtables of the lookups of the `kern` feature of the
+    /// `GPOS` table, grouped by lookup.
+    fn gpos_pair_adjustments(&self) -> Result<Vec<Vec<PairAdjustment<'a>>>, FontError> {
+        let Some(gpos) = self.table(&GPOS) else {
+            return Ok(Vec::new());
+        };
+        let mut reader = Reader::at(gpos, 6);
+        let feature_list = reader.u16()? as usize;
+        let lookup_list = reader.u16()? as usize;
+
+        // The feature is looked up across all scripts and languages.
+        let mut lookup_indices = Vec::new();
+        let mut reader = Reader::at(gpos, feature_list);
+        for _ in 0..reader.u16()? {
+            let tag = reader.tag()?;
+            let offset = reader.u16()? as usize;
+            if tag == KERN {
+                let mut feature = Reader::at(gpos, feature_list + offset + 2);
+                for _ in 0..feature.u16()? {
+                    lookup_indices.push(feature.u16()?);
+                }
+            }
+        }
+        lookup_indices.sort_unstable();
+        lookup_indices.dedup();
+
+        let mut lookups = Vec::new();
+        for index in lookup_indices {
+            let mut reader = Reader::at(gpos, lookup_list + 2 + index as usize * 2);
+            let lookup = lookup_list + reader.u16()? as usize;
+            let mut reader = Reader::at(gpos, lookup);
+            let lookup_type = reader.u16()?;
+            reader.skip(2);
+            let mut subtables = Vec::new();
+            for _ in 0..reader.u16()? {
+                let mut subtable = lookup + reader.u16()? as usize;
+                let mut subtable_type = lookup_type;
+                if lookup_type == EXTENSION {
+                    let mut extension = Reader::at(gpos, subtable + 2);
+                    subtable_type = extension.u16()?;
+                    subtable += extension.u32()? as usize;
+                }
+                if subtable_type == PAIR_ADJUSTMENT {
+                    let data = gpos.get(subtable..).ok_or(FontError::UnexpectedEnd)?;
+                    subtables.push(PairAdjustment::new(data)?);
+                }
+            }
+            lookups.push(subtables);
+        }
+        Ok(lookups)
+    }
+
+    /// Returns the horizontal format 0 subtables of the `kern` table, which are sorted lists of
+    /// glyph pairs, as readers positioned at their first pair, along with their pair counts.
+    fn kern_subtables(&self) -> Result<Vec<(Reader<'a>, usize)>, FontError> {
+        let Some(kern) = self.table(&KERN) else {
+            return Ok(Vec::new());
+        };
+        let mut reader = Reader::new(kern);
+        // The Apple version of the table has a 32-bit version and subtable headers.
+        let apple = reader.u16()? == 1;
+        let num_tables = if apple {
+            reader.skip(2);
+            reader.u32()? as usize
+        } else {
+            reader.u16()? as usize
+        };
+        let mut subtables = Vec::new();
+        for _ in 0..num_tables {
+            let start = reader.pos();
+            let (length, format, horizontal) = if apple {
+                let length = reader.u32()? as usize;
+                let coverage = reader.u16()?;
+                reader.skip(2);
+                (length, coverage & 0xFF, coverage & 0xE000 == 0)
+            } else {
+                reader.skip(2);
+                let length = reader.u16()? as usize;
+                let coverage = reader.u16()?;
+                (length, coverage >> 8, coverage & 0x0007 == 0x0001)
+            };
+            if format == 0 && horizontal {
+                let num_pairs = reader.u16()? as usize;
+                reader.skip(6);
+                subtables.push((reader.clone(), num_pairs));
+            }
+            reader.seek(start + length);
+        }
+        Ok(subtables)
+    }
+
+    fn gpos_kerning(&self, left: u16, right: u16) -> Result<Option<i16>, FontError> {
+        let lookups = self.gpos_pair_adjustments()?;
+        if lookups.is_empty() {
+            return Ok(None);
+        }
+        let mut advance = 0i16;
+        for subtables in lookups {
+            for subtable in subtables {
+                // The first subtable that has the pair applies, even if its adjustment is zero.
+                if let Some(value) = subtable.advance(left, right)? {
+                    advance = advance.saturating_add(value);
+                    break;
+                }
+            }
+        }
+        Ok(Some(advance))
+    }
+
+    fn kern_kerning(&self, left: u16, right: u16) -> Result<i16, FontError> {
+        let key = (left as u32) << 16 | right as u32;
+        let mut advance = 0i16;
+        for (pairs, num_pairs) in self.kern_subtables()? {
+            let (mut low, mut high) = (0, num_pairs);
+            while low < high {
+                let mid = (low + high) / 2;
+                let mut reader = pairs.clone();
+                reader.skip(mid * 6);
+                match reader.u32()?.cmp(&key) {
+                    std::cmp::Ordering::Less => low = mid + 1,
+                    std::cmp::Ordering::Greater => high = mid,
+                    std::cmp::Ordering::Equal => {
+                        advance = advance.saturating_add(reader.i16()?);
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(advance)
+    }
+
+    fn collect_kern_pairs(&self, pairs: &mut BTreeMap<(u16, u16), i16>) -> Result<(), FontError> {
+        for (mut reader, num_pairs) in self.kern_subtables()? {
+            for _ in 0..num_pairs {
+                let pair = (reader.u16()?, reader.u16()?);
+                let total: &mut i16 = pairs.entry(pair).or_default();
+                *total = total.saturating_add(reader.i16()?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the adjustment of the advance of the `left` glyph when it is followed by the
+    /// `right` glyph, in font units. Pair adjustments of the `kern` feature of the `GPOS` table
+    /// are used if there are any, or else the `kern` table. The lookups are parsed again on
+    /// every call, so [`Font::kerning_pairs`] is the better choice for reading many pairs.
+    pub fn kerning(&self, left: u16, right: u16) -> Result<i16, FontError> {
+        if let Some(advance) = self
+            .gpos_kerning(left, right)
+            .map_err(|_| FontError::InvalidTable(GPOS))?
+        {
+            return Ok(advance);
+        }
+        self.kern_kerning(left, right)
+            .map_err(|_| FontError::InvalidTable(KERN))
+    }
+
+    /// Lists every glyph pair with a nonzero kerning adjustment, as returned by
+    /// [`Font::kerning`], sorted by the left and then the right glyph.
+    pub fn kerning_pairs(&self) -> Result<Vec<KerningPair>, FontError> {
+        let lookups = self
+            .gpos_pair_adjustments()
+            .map_err(|_| FontError::InvalidTable(GPOS))?;
+        let mut pairs = BTreeMap::new();
+        if !lookups.is_empty() {
+            for subtables in lookups {
+                // Within a lookup, the first subtable that has the pair applies.
+                let mut lookup_pairs = BTreeMap::new();
+                for subtable in subtables {
+                    subtable
+                        .collect(self.num_glyphs(), &mut lookup_pairs)
+                        .map_err(|_| FontError::InvalidTable(GPOS))?;
+                }
+                for (pair, advance) in lookup_pairs {
+                    let total: &mut i16 = pairs.entry(pair).or_default();
+                    *total = total.saturating_add(advance);
+                }
+            }
+        } else {
+            self.collect_kern_pairs(&mut pairs)
+                .map_err(|_| FontError::InvalidTable(KERN))?;
+        }
+        Ok(pairs
+            .into_iter()
+            .filter(|&(_, advance)| advance != 0)
+            .map(|((left, right), advance)| KerningPair {
+                left,
+                right,
+                advance,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::font::tests::{font_data, words};
+
+    fn hhea(ascender: i32, descender: i32, line_gap: i32) -> Vec<u8> {
+        let mut hhea = vec![0; 36];
+        hhea[4..10].copy_from_slice(&words(&[ascender, descender, line_gap]));
+        hhea
+    }
+
+    /// A version 1 `OS/2` table with the given typographic metrics.
+    fn os2(fs_selection: u16, metrics: [i32; 3]) -> Vec<u8> {
+        let mut os2 = vec![0; 78];
+        os2[62..64].copy_from_slice(&fs_selection.to_be_bytes());
+        os2[68..74].copy_from_slice(&words(&metrics));
+        os2
+    }
+
+    fn metrics(tables: &[(&[u8; 4], &[u8])]) -> Result<FontMetrics, FontError> {
+        Font::from_bytes(&font_data(1, tables)).unwrap().metrics()
+    }
+
+    fn coverage_format_1(glyphs: &[i32]) -> Vec<u8> {
+        let mut coverage = words(&[1, glyphs.len() as i32]);
+        coverage.extend(words(glyphs));
+        coverage
+    }
+
+    /// A coverage table of glyph ranges, given as their first and last glyphs and the coverage
+    /// index of the first one.
+    fn coverage_format_2(ranges: &[[i32; 3]]) -> Vec<u8> {
+        let mut coverage = words(&[2, ranges.len() as i32]);
+        coverage.extend(words(ranges.concat().as_slice()));
+        coverage
+    }
+
+    fn class_def_format_1(start: i32, classes: &[i32]) -> Vec<u8> {
+        let mut class_def = words(&[1, start, classes.len() as i32]);
+        class_def.extend(words(classes));
+        class_def
+    }
+
+    /// A class definition table of glyph ranges, given as their first and last glyphs and
+    /// their class.
+    fn class_def_format_2(ranges: &[[i32; 3]]) -> Vec<u8> {
+        let mut class_def = words(&[2, ranges.len() as i32]);
+        class_def.extend(words(ranges.concat().as_slice()));
+        class_def
+    }
+
+    /// A format 1 pair adjustment subtable with a pair set of second glyphs and advance
+    /// adjustments for each glyph of the coverage table.
+    fn pair_sets(coverage: Vec<u8>, sets: &[&[[i32; 2]]]) -> Vec<u8> {
+        let mut offset = 10 + 2 * sets.len();
+        let mut pair_sets = Vec::new();
+        for set in sets {
+            pair_sets.extend(words(&[offset as i32]));
+            offset += 2 + 4 * set.len();
+        }
+        let mut subtable = words(&[1, offset as i32, X_ADVANCE as i32, 0, sets.len() as i32]);
+        subtable.extend(pair_sets);
+        for set in sets {
+            subtable.extend(words(&[set.len() as i32]));
+            subtable.extend(words(set.concat().as_slice()));
+        }
+        subtable.extend(coverage);
+        subtable
+    }
+
+    /// A format 2 pair adjustment subtable with the advance adjustments of each pair of
+    /// classes, indexed by the class of the first glyph and then that of the second one.
+    fn class_pairs(
+        coverage: Vec<u8>,
+        class_def1: Vec<u8>,
+        class_def2: Vec<u8>,
+        advances: &[&[i32]],
+    ) -> Vec<u8> {
+        let coverage_offset = 16 + 2 * advances.concat().len();
+        let class_def1_offset = coverage_offset + coverage.len();
+        let class_def2_offset = class_def1_offset + class_def1.len();
+        let mut subtable = words(&[
+            2,
+            coverage_offset as i32,
+            X_ADVANCE as i32,
+            0,
+            class_def1_offset as i32,
+            class_def2_offset as i32,
+            advances.len() as i32,
+            advances[0].len() as i32,
+        ]);
+        subtable.extend(words(advances.concat().as_slice()));
+        subtable.extend(coverage);
+        subtable.extend(class_def1);
+        subtable.extend(class_def2);
+        subtable
+    }
+
+    /// Wraps a pair adjustment subtable in an extension subtable.
+    fn extension(subtable: Vec<u8>) -> Vec<u8> {
+        let mut extension = words(&[1, PAIR_ADJUSTMENT as i32]);
+        extension.extend(8u32.to_be_bytes());
+        extension.extend(subtable);
+        extension
+    }
+
+    /// A `GPOS` table whose `kern` feature uses each of the lookups, which are given as their
+    /// lookup type and subtables.
+    fn gpos(lookups: &[(u16, Vec<Vec<u8>>)]) -> Vec<u8> {
+        let lookup_list = 10 + 8 + 4 + 2 * lookups.len();
+        let mut gpos = words(&[1, 0, 0, 10, lookup_list as i32]);
+        gpos.extend(words(&[1]));
+        gpos.extend(b"kern");
+        gpos.extend(words(&[8, 0, lookups.len() as i32]));
+        gpos.extend(words(&(0..lookups.len() as i32).collect::<Vec<_>>()));
+        let mut offset = 2 + 2 * lookups.len();
+        let mut tables = Vec::new();
+        gpos.extend(words(&[lookups.len() as i32]));
+        for (lookup_type, subtables) in lookups {
+            gpos.extend(words(&[offset as i32]));
+            let mut subtable_offset = 6 + 2 * subtables.len();
+            let mut lookup = words(&[*lookup_type as i32, 0, subtables.len() as i32]);
+            for subtable in subtables {
+                lookup.extend(words(&[subtable_offset as i32]));
+                subtable_offset += subtable.len();
+            }
+            lookup.extend(subtables.concat());
+            offset += lookup.len();
+            tables.extend(lookup);
+        }
+        gpos.extend(tables);
+        gpos
+    }
+
+    /// A `kern` table with the Microsoft header, given each subtable's coverage and pairs.
+    fn microsoft_kern(subtables: &[(u16, &[[i32; 3]])]) -> Vec<u8> {
+        let mut kern = words(&[0, subtables.len() as i32]);
+        for (coverage, pairs) in subtables {
+            let length = 14 + 6 * pairs.len() as i32;
+            kern.extend(words(&[
+                0,
+                length,
+                *coverage as i32,
+                pairs.len() as i32,
+                0,
+                0,
+                0,
+            ]));
+            kern.extend(words(pairs.concat().as_slice()));
+        }
+        kern
+    }
+
+    /// A `kern` table with the Apple header, given each subtable's coverage and pairs.
+    fn apple_kern(subtables: &[(u16, &[[i32; 3]])]) -> Vec<u8> {
+        let mut kern = words(&[1, 0]);
+        kern.extend((subtables.len() as u32).to_be_bytes());
+        for (coverage, pairs) in subtables {
+            kern.extend((16 + 6 * pairs.len() as u32).to_be_bytes());
+            kern.extend(words(&[*coverage as i32, 0, pairs.len() as i32, 0, 0, 0]));
+            kern.extend(words(pairs.concat().as_slice()));
+        }
+        kern
+    }
+
+    fn kerning_pairs(font: &Font) -> Vec<[i32; 3]> {
+        let pairs = font.kerning_pairs().unwrap();
+        for pair in &pairs {
+            assert_eq!(font.kerning(pair.left, pair.right).unwrap(), pair.advance);
+        }
+        pairs
+            .iter()
+            .map(|pair| [pair.left as i32, pair.right as i32, pair.advance as i32])
+            .collect()
+    }
+
+    #[test]
+    fn reads_hhea_metrics() {
+        let hhea = hhea(800, -200, 90);
+        let metrics = metrics(&[(b"hhea", &hhea), (b"OS/2", &os2(0, [700, -300, 0]))]);
+        let expected = FontMetrics {
+            units_per_em: 1000,
+            ascender: 800,
+            descender: -200,
+            line_gap: 90,
+        };
+        assert_eq!(metrics.unwrap(), expected);
+        assert_eq!(expected.line_height(), 1090);
+    }
+
+    #[test]
+    fn prefers_typographic_metrics() {
+        let hhea = hhea(800, -200, 90);
+        let os2_metrics = |os2: &[u8]| {
+            let metrics = metrics(&[(b"hhea", &hhea), (b"OS/2", os2)]).unwrap();
+            (metrics.ascender, metrics.descender, metrics.line_gap)
+        };
+        let os2_typo = os2(USE_TYPO_METRICS, [700, -300, 0]);
+        assert_eq!(os2_metrics(&os2_typo), (700, -300, 0));
+        // A version 0 table is too short to have typographic metrics.
+        assert_eq!(os2_metrics(&os2_typo[..68]), (800, -200, 90));
+        let os2 = os2(0, [700, -300, 0]);
+        let os2_only = metrics(&[(b"OS/2", &os2)]).unwrap();
+        assert_eq!((os2_only.ascender, os2_only.descender), (700, -300));
+        assert_eq!(
+            metrics(&[(b"OS/2", &os2_typo[..68])]),
+            Err(FontError::MissingTable(*b"hhea"))
+        );
+    }
+
+    #[test]
+    fn stops_at_first_matching_subtable() {
+        // The first subtable zeroes the pair (1, 2), which the second one would move closer.
+        let gpos = gpos(&[(
+            PAIR_ADJUSTMENT,
+            vec![
+                pair_sets(coverage_format_1(&[1]), &[&[[2, 0]]]),
+                class_pairs(
+                    coverage_format_1(&[1]),
+                    class_def_format_1(1, &[0]),
+                    class_def_format_1(2, &[1, 1]),
+                    &[&[0, -50]],
+                ),
+            ],
+        )]);
+        let data = font_data(5, &[(b"GPOS", &gpos)]);
+        let font = Font::from_bytes(&data).unwrap();
+        assert_eq!(font.kerning(1, 2).unwrap(), 0);
+        assert_eq!(font.kerning(1, 4).unwrap(), 0);
+        assert_eq!(font.kerning(2, 3).unwrap(), 0);
+        assert_eq!(kerning_pairs(&font), [[1, 3, -50]]);
+    }
+
+    #[test]
+    fn reads_coverage_and_class_ranges() {
+        let gpos = gpos(&[(
+            PAIR_ADJUSTMENT,
+            vec![
+                pair_sets(
+                    coverage_format_2(&[[4, 5, 0], [8, 8, 2]]),
+                    &[&[[1, -10]], &[[1, -20]], &[[1, -30], [2, -40]]],
+                ),
+                class_pairs(
+                    coverage_format_2(&[[1, 3, 0]]),
+                    class_def_format_2(&[[1, 1, 1], [3, 3, 1]]),
+                    class_def_format_2(&[[6, 7, 1]]),
+                    &[&[0, 5], &[0, 15]],
+                ),
+            ],
+        )]);
+        let data = font_data(10, &[(b"GPOS", &gpos)]);
+        let font = Font::from_bytes(&data).unwrap();
+        assert_eq!(font.kerning(6, 1).unwrap(), 0);
+        assert_eq!(font.kerning(3, 8).unwrap(), 0);
+        assert_eq!(
+            kerning_pairs(&font),
+            [
+                [1, 6, 15],
+                [1, 7, 15],
+                [2, 6, 5],
+                [2, 7, 5],
+                [3, 6, 15],
+                [3, 7, 15],
+                [4, 1, -10],
+                [5, 1, -20],
+                [8, 1, -30],
+                [8, 2, -40],
+            ]
+        );
+    }
+
+    #[test]
+    fn follows_extension_lookups() {
+        let subtable = pair_sets(coverage_format_1(&[1]), &[&[[2, -25]]]);
+        let gpos = gpos(&[(EXTENSION, vec![extension(subtable)])]);
+        let data = font_data(3, &[(b"GPOS", &gpos)]);
+        let font = Font::from_bytes(&data).unwrap();
+        assert_eq!(kerning_pairs(&font), [[1, 2, -25]]);
+    }
+
+    #[test]
+    fn sums_lookups() {
+        let gpos = gpos(&[
+            (
+                PAIR_ADJUSTMENT,
+                vec![pair_sets(coverage_format_1(&[1]), &[&[[2, -20], [3, 20]]])],
+            ),
+            (
+                PAIR_ADJUSTMENT,
+                vec![pair_sets(coverage_format_1(&[1]), &[&[[2, -30], [3, -20]]])],
+            ),
+        ]);
+        let kern = microsoft_kern(&[(0x0001, &[[1, 2, -100]])]);
+        let data = font_data(4, &[(b"GPOS", &gpos), (b"kern", &kern)]);
+        let font = Font::from_bytes(&data).unwrap();
+        // The pairs cancel out in (1, 3), and the `kern` table is ignored.
+        assert_eq!(font.kerning(1, 3).unwrap(), 0);
+        assert_eq!(kerning_pairs(&font), [[1, 2, -50]]);
+    }
+
+    #[test]
+    fn falls_back_to_kern_table() {
+        let pairs: &[[i32; 3]] = &[[1, 2, -50], [1, 3, 20]];
+        let horizontal = (0x0001, pairs);
+        let cross_stream = (0x0005, &[[1, 2, -100]][..]);
+        let extra = (0x0001, &[[1, 2, -5]][..]);
+        let microsoft = microsoft_kern(&[horizontal, cross_stream, extra]);
+        let horizontal = (0x0000, pairs);
+        let vertical = (0x8000, &[[1, 2, -100]][..]);
+        let extra = (0x0000, &[[1, 2, -5]][..]);
+        let apple = apple_kern(&[horizontal, vertical, extra]);
+        for kern in [microsoft, apple] {
+            let data = font_data(4, &[(b"kern", &kern)]);
+            let font = Font::from_bytes(&data).unwrap();
+            assert_eq!(font.kerning(2, 1).unwrap(), 0);
+            assert_eq!(kerning_pairs(&font), [[1, 2, -55], [1, 3, 20]]);
+        }
+    }
+}
@@ -0,0 +1,573 @@
+use crate::{Font, FontError, OutlineBuilder, Reader, Shape, Vector2};
+
+const ON_CURVE_POINT: u8 = 0x01;
+const X_SHORT_VECTOR: u8 = 0x02;
+const Y_SHORT_VECTOR: u8 = 0x04;
+const REPEAT_FLAG: u8 = 0x08;
+const X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR: u8 = 0x10;
+const Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR: u8 = 0x20;
+
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+const SCALED_COMPONENT_OFFSET: u16 = 0x0800;
+
+/// The maximum nesting of composite glyphs, which guards against cyclic references.
+const MAX_COMPONENT_DEPTH: usize = 16;
+
+const GLYF: [u8; 4] = *b"glyf";
+
+#[derive(Clone, Copy)]
+pub(crate) struct OutlinePoint {
+    pub(crate) p: Vector2,
+    pub(crate) on_curve: bool,
+}
+
+/// The points of a TrueType glyph, with composite glyphs resolved.
+#[derive(Default)]
+pub(crate) struct GlyphOutline {
+    pub(crate) points: Vec<OutlinePoint>,
+    /// The index one past the last point of each contour.
+    pub(crate) contour_ends: Vec<usize>,
+    /// The deltas of the glyph's phantom points at the selected instance of a variable font,
+    /// which move its horizontal and vertical metrics.
+    pub(crate) phantom_deltas: [Vector2; 4],
+}
+
+impl GlyphOutline {
+    /// Builds the shape, inserting the implied on-curve points between consecutive off-curve
+    /// points.
+    pub(crate) fn to_shape(&self) -> Shape {
+        let mut builder = OutlineBuilder::new();
+        let mut start = 0;
+        for &end in &self.contour_ends {
+            append_contour(&mut builder, &self.points[start..end]);
+            start = end;
+        }
+        builder.finish()
+    }
+}
+
+fn append_contour(builder: &mut OutlineBuilder, points: &[OutlinePoint]) {
+    let n = points.len();
+    if n == 0 {
+        return;
+    }
+    // Start at the first on-curve point, or if there is none, at the implied point between the
+    // last and first off-curve points.
+    let (start, first) = match points.iter().position(|p| p.on_curve) {
+        Some(i) => (points[i].p, i + 1),
+        None => ((points[n - 1].p + points[0].p) * 0.5, 0),
+    };
+    builder.move_to(start);
+    let mut control: Option<Vector2> = None;
+    for i in first..first + n {
+        let point = points[i % n];
+        match (point.on_curve, control) {
+            (true, Some(c)) => {
+                builder.quadratic_to(c, point.p);
+                control = None;
+            }
+            (true, None) => builder.line_to(point.p),
+            (false, Some(c)) => {
+                builder.quadratic_to(c, (c + point.p) * 0.5);
+                control = Some(point.p);
+            }
+            (false, None) => control = Some(point.p),
+        }
+    }
+    if let Some(c) = control {
+        builder.quadratic_to(c, start);
+    }
+    builder.close();
+}
+
+/// A component of a composite glyph.
+struct Component {
+    flags: u16,
+    glyph: u16,
+    /// Either the offset of the component, or the indices of the points to align.
+    arg1: i32,
+    arg2: i32,
+    /// The transformation, which maps (x, y) to (a * x + c * y, b * x + d * y).
+    matrix: [f64; 4],
+}
+
+impl Component {
+    fn read(reader: &mut Reader) -> Result<Self, FontError> {
+        let flags = reader.u16()?;
+        let glyph = reader.u16()?;
+        let (arg1, arg2) = match (
+            flags & ARG_1_AND_2_ARE_WORDS != 0,
+            flags & ARGS_ARE_XY_VALUES != 0,
+        ) {
+            (true, true) => (reader.i16()? as i32, reader.i16()? as i32),
+            (true, false) => (reader.u16()? as i32, reader.u16()? as i32),
+            (false, true) => (reader.i8()? as i32, reader.i8()? as i32),
+            (false, false) => (reader.u8()? as i32, reader.u8()? as i32),
+        };
+        let matrix = if flags & WE_HAVE_A_SCALE != 0 {
+            let scale = reader.f2dot14()?;
+            [scale, 0.0, 0.0, scale]
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            [reader.f2dot14()?, 0.0, 0.0, reader.f2dot14()?]
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            [
+                reader.f2dot14()?,
+                reader.f2dot14()?,
+                reader.f2dot14()?,
+                reader.f2dot14()?,
+            ]
+        } else {
+            [1.0, 0.0, 0.0, 1.0]
+        };
+        Ok(Component {
+            flags,
+            glyph,
+            arg1,
+            arg2,
+            matrix,
+        })
+    }
+}
+
+impl<'a> Font<'a> {
+    /// Returns the glyph's data in the `glyf` table, which is empty if it has no outline.
+    fn glyph_data(&self, glyph: u16) -> Result<&'a [u8], FontError> {
+        self.check_glyph(glyph)?;
+        let loca = self.required_table(b"loca")?;
+        let glyf = self.required_table(&GLYF)?;
+        let (start, end) = if self.index_to_loc_format() == 0 {
+            let mut reader = Reader::at(loca, glyph as usize * 2);
+            (reader.u16()? as usize * 2, reader.u16()? as usize * 2)
+        } else {
+            let mut reader = Reader::at(loca, glyph as usize * 4);
+            (reader.u32()? as usize, reader.u32()? as usize)
+        };
+        if start > end {
+            return Err(FontError::InvalidTable(*b"loca"));
+        }
+        glyf.get(start..end).ok_or(FontError::InvalidTable(GLYF))
+    }
+
+    pub(crate) fn glyph_outline(&self, glyph: u16) -> Result<GlyphOutline, FontError> {
+        let mut outline = GlyphOutline::default();
+        self.append_glyph_outline(&mut outline, glyph, 0)?;
+        Ok(outline)
+    }
+
+    fn append_glyph_outline(
+        &self,
+        outline: &mut GlyphOutline,
+        glyph: u16,
+        depth: usize,
+    ) -> Result<(), FontError> {
+        let data = self.glyph_data(glyph)?;
+        if data.is_empty() {
+            return Ok(());
+        }
+        let mut reader = Reader::new(data);
+        let num_contours = reader.i16()?;
+        reader.skip(8);
+        if num_contours < 0 {
+            return self.read_composite_glyph(&mut reader, outline, glyph, depth);
+        }
+        let first_point = outline.points.len();
+        let first_contour = outline.contour_ends.len();
+        read_simple_glyph(&mut reader, num_contours as usize, outline)?;
+        let points: Vec<Vector2> = outline.points[first_point..].iter().map(|p| p.p).collect();
+        let contour_ends: Vec<usize> = outline.contour_ends[first_contour..]
+            .iter()
+            .map(|end| end - first_point)
+            .collect();
+        if let Some(deltas) = self.glyph_deltas(glyph, &points, &contour_ends)? {
+            let (deltas, phantom_deltas) = deltas.split_at(points.len());
+            for (point, delta) in outline.points[first_point..].iter_mut().zip(deltas) {
+                point.p += *delta;
+            }
+            if depth == 0 {
+                outline.phantom_deltas.copy_from_slice(phantom_deltas);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_composite_glyph(
+        &self,
+        reader: &mut Reader,
+        outline: &mut GlyphOutline,
+        glyph: u16,
+        depth: usize,
+    ) -> Result<(), FontError> {
+        if depth >= MAX_COMPONENT_DEPTH {
+            return Err(FontError::InvalidTable(GLYF));
+        }
+        let mut components = Vec::new();
+        loop {
+            let component = Component::read(reader)?;
+            let more = component.flags & MORE_COMPONENTS != 0;
+            components.push(component);
+            if !more {
+                break;
+            }
+        }
+        // The variations of a composite glyph move its components' offsets.
+        let offsets: Vec<Vector2> = components
+            .iter()
+            .map(|c| Vector2::new(c.arg1 as f64, c.arg2 as f64))
+            .collect();
+        let deltas = self.glyph_deltas(glyph, &offsets, &[])?;
+        if let (0, Some(deltas)) = (depth, &deltas) {
+            outline
+                .phantom_deltas
+                .copy_from_slice(&deltas[offsets.len()..]);
+        }
+
+        for (i, component) in components.iter().enumerate() {
+            let flags = component.flags;
+            let [a, b, c, d] = component.matrix;
+            let transform = |p: Vector2| Vector2::new(a * p.x + c * p.y, b * p.x + d * p.y);
+
+            let first_point = outline.points.len();
+            self.append_glyph_outline(outline, component.glyph, depth + 1)?;
+            for point in &mut outline.points[first_point..] {
+                point.p = transform(point.p);
+            }
+            let offset = if flags & ARGS_ARE_XY_VALUES != 0 {
+                let delta = deltas.as_ref().map_or(Vector2::default(), |d| d[i]);
+                let offset = offsets[i] + delta;
+                if flags & SCALED_COMPONENT_OFFSET != 0 {
+                    transform(offset)
+                } else {
+                    offset
+                }
+            } else {
+                // Align a point of the component with a point of the glyph so far.
+                let parent = outline.points[..first_point].get(component.arg1 as usize);
+                let child = outline.points[first_point..].get(component.arg2 as usize);
+                match (parent, child) {
+                    (Some(parent), Some(child)) => parent.p - child.p,
+                    _ => return Err(FontError::InvalidTable(GLYF)),
+                }
+            };
+            for point in &mut outline.points[first_point..] {
+                point.p += offset;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn truetype_glyph_shape(&self, glyph: u16) -> Result<Shape, FontError> {
+        Ok(self.glyph_outline(glyph)?.to_shape())
+    }
+
+    /// Computes how much the glyph's advance width and left side bearing change at the selected
+    /// instance of a variable font, from the phantom points of its TrueType outline. Returns
+    /// `None` if the font has no TrueType outlines or the glyph can't be read.
+    pub(crate) fn horizontal_metrics_deltas(&self, glyph: u16) -> Option<(f64, f64)> {
+        self.table(&GLYF)?;
+        let data = self.glyph_data(glyph).ok()?;
+        let outline = self.glyph_outline(glyph).ok()?;
+        let [left, right, ..] = outline.phantom_deltas;
+        // The bearing is the distance from the left phantom point to the outline's left edge,
+        // which the variation may move as well.
+        let x_min = if data.is_empty() {
+            0.0
+        } else {
+            Reader::at(data, 2).i16().ok()? as f64
+        };
+        let varied_x_min = outline
+            .points
+            .iter()
+            .map(|point| point.p.x)
+            .reduce(f64::min)
+            .unwrap_or(x_min);
+        Some((right.x - left.x, varied_x_min - x_min - left.x))
+    }
+}
+
+fn read_simple_glyph(
+    reader: &mut Reader,
+    num_contours: usize,
+    outline: &mut GlyphOutline,
+) -> Result<(), FontError> {
+    let first_point = outline.points.len();
+    let mut num_points = 0;
+    for _ in 0..num_contours {
+        let end = reader.u16()? as usize + 1;
+        if end < num_points {
+            return Err(FontError::InvalidTable(GLYF));
+        }
+        num_points = end;
+        outline.contour_ends.push(first_point + end);
+    }
+    let instruction_length = reader.u16()? as usize;
+    reader.skip(instruction_length);
+
+    let mut flags = Vec::with_capacity(num_points);
+    while flags.len() < num_points {
+        let flag = reader.u8()?;
+        let count = if flag & REPEAT_FLAG != 0 {
+            reader.u8()? as usize + 1
+        } else {
+            1
+        };
+        flags.extend(std::iter::repeat_n(flag, count));
+    }
+    flags.truncate(num_points);
+
+    let read_coordinates = |reader: &mut Reader, short: u8, same_or_positive: u8| {
+        let mut value = 0i32;
+        flags
+            .iter()
+            .map(|&flag| {
+                if flag & short != 0 {
+                    let delta = reader.u8()? as i32;
+                    value += if flag & same_or_positive != 0 {
+                        delta
+                    } else {
+                        -delta
+                    };
+                } else if flag & same_or_positive == 0 {
+                    value += reader.i16()? as i32;
+                }
+                Ok(value as f64)
+            })
+            .collect::<Result<Vec<f64>, FontError>>()
+    };
+    let xs = read_coordinates(reader, X_SHORT_VECTOR, X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR)?;
+    let ys = read_coordinates(reader, Y_SHORT_VECTOR, Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR)?;
+    outline.points.extend(
+        flags
+            .iter()
+            .zip(xs)
+            .zip(ys)
+            .map(|((&flag, x), y)| OutlinePoint {
+                p: Vector2::new(x, y),
+                on_curve: flag & ON_CURVE_POINT != 0,
+            }),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::font::tests::{font_data, words};
+    use crate::{LinearSegment, QuadraticSegment, Segment};
+
+    fn v(x: f64, y: f64) -> Vector2 {
+        Vector2::new(x, y)
+    }
+
+    fn outline(points: &[(f64, f64, bool)], contour_ends: &[usize]) -> GlyphOutline {
+        GlyphOutline {
+            points: points
+                .iter()
+                .map(|&(x, y, on_curve)| OutlinePoint {
+                    p: v(x, y),
+                    on_curve,
+                })
+                .collect(),
+            contour_ends: contour_ends.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// The points of each edge of each contour, with lines as two points and quadratics as
+    /// three.
+    fn edges(shape: &Shape) -> Vec<Vec<Vec<Vector2>>> {
+        shape
+            .contours
+            .iter()
+            .map(|contour| {
+                contour
+                    .edges
+                    .iter()
+                    .map(|edge| match edge.segment {
+                        Segment::Linear(LinearSegment(p0, p1)) => vec![p0, p1],
+                        Segment::Quadratic(QuadraticSegment(p0, p1, p2)) => vec![p0, p1, p2],
+                        Segment::Cubic(_) => unreachable!(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// A font whose `glyf` table holds the given glyphs, along with the other given tables.
+    pub(crate) fn truetype_font_data(glyphs: &[Vec<u8>], tables: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+        let mut loca = vec![0, 0, 0, 0];
+        let mut glyf = Vec::new();
+        for glyph in glyphs {
+            glyf.extend(glyph);
+            loca.extend((glyf.len() as u32).to_be_bytes());
+        }
+        let mut tables = tables.to_vec();
+        tables.extend([(b"glyf", &glyf[..]), (b"loca", &loca[..])]);
+        font_data(glyphs.len() as u16, &tables)
+    }
+
+    /// A simple glyph with the triangle (0, 0), (100, 0), (0, 100).
+    pub(crate) fn triangle() -> Vec<u8> {
+        let mut data = words(&[1, 0, 0, 100, 100, 2, 0]);
+        data.extend([0x31, 0x33, 0x27]);
+        data.extend([100, 100]);
+        data.extend([100]);
+        data
+    }
+
+    #[test]
+    fn decodes_flags_and_coordinates() {
+        // Two contours of 3 points, with 2 bytes of instructions and a repeated flag.
+        let mut data = words(&[2, 5, 2]);
+        data.extend([0xAA, 0xBB]);
+        data.extend([0x37, 0x10, 0x21, 0x14, 0x3F, 0x01]);
+        data.extend([10]);
+        data.extend(words(&[-300]));
+        data.extend([1, 1]);
+        data.extend([20]);
+        data.extend(words(&[300]));
+        data.extend([5, 1, 1]);
+        let mut outline = GlyphOutline::default();
+        read_simple_glyph(&mut Reader::new(&data), 2, &mut outline).unwrap();
+        assert_eq!(outline.contour_ends, [3, 6]);
+        let points: Vec<(f64, f64, bool)> = outline
+            .points
+            .iter()
+            .map(|point| (point.p.x, point.p.y, point.on_curve))
+            .collect();
+        assert_eq!(
+            points,
+            [
+                (10.0, 20.0, true),
+                (10.0, 320.0, false),
+                (-290.0, 320.0, true),
+                (-290.0, 315.0, false),
+                (-289.0, 316.0, true),
+                (-288.0, 317.0, true),
+            ]
+        );
+        data.pop();
+        let mut outline = GlyphOutline::default();
+        assert_eq!(
+            read_simple_glyph(&mut Reader::new(&data), 2, &mut outline).err(),
+            Some(FontError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn inserts_implied_on_curve_points() {
+        let shape = outline(
+            &[
+                (0.0, 0.0, true),
+                (0.0, 10.0, false),
+                (10.0, 10.0, false),
+                (10.0, 0.0, true),
+            ],
+            &[4],
+        )
+        .to_shape();
+        assert_eq!(
+            edges(&shape),
+            [[
+                vec![v(0.0, 0.0), v(0.0, 10.0), v(5.0, 10.0)],
+                vec![v(5.0, 10.0), v(10.0, 10.0), v(10.0, 0.0)],
+                vec![v(10.0, 0.0), v(0.0, 0.0)],
+            ]]
+        );
+        // A contour that starts with an off-curve point starts at its first on-curve point.
+        let shape = outline(
+            &[(0.0, 10.0, false), (10.0, 0.0, true), (0.0, 0.0, true)],
+            &[3],
+        )
+        .to_shape();
+        assert_eq!(
+            edges(&shape),
+            [[
+                vec![v(10.0, 0.0), v(0.0, 0.0)],
+                vec![v(0.0, 0.0), v(0.0, 10.0), v(10.0, 0.0)],
+            ]]
+        );
+    }
+
+    #[test]
+    fn builds_all_off_curve_contours() {
+        let shape = outline(
+            &[
+                (0.0, 0.0, false),
+                (0.0, 10.0, false),
+                (10.0, 10.0, false),
+                (10.0, 0.0, false),
+            ],
+            &[4],
+        )
+        .to_shape();
+        assert_eq!(
+            edges(&shape),
+            [[
+                vec![v(5.0, 0.0), v(0.0, 0.0), v(0.0, 5.0)],
+                vec![v(0.0, 5.0), v(0.0, 10.0), v(5.0, 10.0)],
+                vec![v(5.0, 10.0), v(10.0, 10.0), v(10.0, 5.0)],
+                vec![v(10.0, 5.0), v(10.0, 0.0), v(5.0, 0.0)],
+            ]]
+        );
+    }
+
+    #[test]
+    fn resolves_composite_glyphs() {
+        // The triangle scaled by 1/2 and offset by (10, 20), then rotated by 90 degrees with
+        // its point 2 aligned to point 1 of the glyph so far.
+        let mut composite = words(&[-1, 0, 0, 0, 0]);
+        composite.extend(words(&[0x2B, 0, 10, 20, 0x2000]));
+        composite.extend(words(&[0x80, 0]));
+        composite.extend([1, 2]);
+        composite.extend(words(&[0, 0x4000, -0x4000, 0]));
+        let data = truetype_font_data(&[triangle(), composite, Vec::new()], &[]);
+        let font = Font::from_bytes(&data).unwrap();
+        let points: Vec<Vector2> = font
+            .glyph_outline(1)
+            .unwrap()
+            .points
+            .iter()
+            .map(|point| point.p)
+            .collect();
+        assert_eq!(
+            points,
+            [
+                v(10.0, 20.0),
+                v(60.0, 20.0),
+                v(10.0, 70.0),
+                v(160.0, 20.0),
+                v(160.0, 120.0),
+                v(60.0, 20.0),
+            ]
+        );
+        assert_eq!(font.glyph_shape(1).unwrap().contours.len(), 2);
+        assert!(font.glyph_shape(2).unwrap().contours.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_composites() {
+        // Aligning to a point that doesn't exist.
+        let mut composite = words(&[-1, 0, 0, 0, 0, 0x22, 0]);
+        composite.extend([0, 0]);
+        composite.extend(words(&[0, 0]));
+        composite.extend([3, 0]);
+        // A glyph that includes itself.
+        let mut cycle = words(&[-1, 0, 0, 0, 0, 0x02, 2]);
+        cycle.extend([0, 0]);
+        let data = truetype_font_data(&[triangle(), composite, cycle], &[]);
+        let font = Font::from_bytes(&data).unwrap();
+        assert_eq!(
+            font.glyph_shape(1).err(),
+            Some(FontError::InvalidTable(GLYF))
+        );
+        assert_eq!(
+            font.glyph_shape(2).err(),
+            Some(FontError::InvalidTable(GLYF))
+        );
+    }
+}
@@ -0,0 +1,664 @@
+use crate::{Font, FontError, Reader, Vector2};
+
+const SHARED_POINT_NUMBERS: u16 = 0x8000;
+const COUNT_MASK: u16 = 0x0FFF;
+const EMBEDDED_PEAK_TUPLE: u16 = 0x8000;
+const INTERMEDIATE_REGION: u16 = 0x4000;
+const PRIVATE_POINT_NUMBERS: u16 = 0x2000;
+const TUPLE_INDEX_MASK: u16 = 0x0FFF;
+
+const POINTS_ARE_WORDS: u8 = 0x80;
+const POINT_RUN_COUNT_MASK: u8 = 0x7F;
+const DELTAS_ARE_ZERO: u8 = 0x80;
+const DELTAS_ARE_WORDS: u8 = 0x40;
+const DELTA_RUN_COUNT_MASK: u8 = 0x3F;
+
+/// The points that follow a glyph's outline, which represent its horizontal and vertical
+/// metrics.
+const PHANTOM_POINTS: usize = 4;
+
+const GVAR: [u8; 4] = *b"gvar";
+
+/// An axis of a variable font, from the `fvar` table.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct VariationAxis {
+    /// The axis tag, such as `wght` or `wdth`.
+    pub tag: [u8; 4],
+    pub min_value: f64,
+    pub default_value: f64,
+    pub max_value: f64,
+    /// Whether the axis should not be exposed in user interfaces.
+    pub hidden: bool,
+    /// The `name` table entry of the axis's display name.
+    pub name_id: u16,
+}
+
+impl VariationAxis {
+    /// Maps a value on the axis to the range -1 to 1, where 0 is the default.
+    fn normalize(&self, value: f64) -> f64 {
+        let value = value.clamp(self.min_value, self.max_value);
+        if value < self.default_value {
+            (value - self.default_value) / (self.default_value - self.min_value)
+        } else if value > self.default_value {
+            (value - self.default_value) / (self.max_value - self.default_value)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Which points of a glyph a tuple variation has deltas for.
+#[derive(Clone)]
+enum PointNumbers {
+    All,
+    List(Vec<usize>),
+}
+
+fn read_packed_points(reader: &mut Reader) -> Result<PointNumbers, FontError> {
+    let first = reader.u8()? as usize;
+    if first == 0 {
+        return Ok(PointNumbers::All);
+    }
+    let count = if first & 0x80 != 0 {
+        (first & 0x7F) << 8 | reader.u8()? as usize
+    } else {
+        first
+    };
+    // Point numbers are stored as differences from the previous one.
+    let mut points = Vec::with_capacity(count);
+    let mut point = 0;
+    while points.len() < count {
+        let control = reader.u8()?;
+        let run = (control & POINT_RUN_COUNT_MASK) as usize + 1;
+        for _ in 0..run {
+            point += if control & POINTS_ARE_WORDS != 0 {
+                reader.u16()? as usize
+            } else {
+                reader.u8()? as usize
+            };
+            points.push(point);
+        }
+    }
+    points.truncate(count);
+    Ok(PointNumbers::List(points))
+}
+
+fn read_packed_deltas(reader: &mut Reader, count: usize) -> Result<Vec<f64>, FontError> {
+    let mut deltas = Vec::with_capacity(count);
+    while deltas.len() < count {
+        let control = reader.u8()?;
+        let run = (control & DELTA_RUN_COUNT_MASK) as usize + 1;
+        for _ in 0..run {
+            let delta = match control & (DELTAS_ARE_ZERO | DELTAS_ARE_WORDS) {
+                DELTAS_ARE_ZERO => 0.0,
+                DELTAS_ARE_WORDS => reader.i16()? as f64,
+                0 => reader.i8()? as f64,
+                _ => reader.i32()? as f64,
+            };
+            deltas.push(delta);
+        }
+    }
+    deltas.truncate(count);
+    Ok(deltas)
+}
+
+/// Computes how much a tuple variation applies at the normalized coordinates. Without an
+/// intermediate region, the region spans from 0 to the peak on each axis.
+fn tuple_scalar(coords: &[f64], peak: &[f64], intermediate: Option<(&[f64], &[f64])>) -> f64 {
+    let mut scalar = 1.0;
+    for (axis, &peak) in peak.iter().enumerate() {
+        let coord = coords.get(axis).copied().unwrap_or(0.0);
+        if peak == 0.0 || coord == peak {
+            continue;
+        }
+        let (start, end) = match intermediate {
+            Some((start, end)) => (start[axis], end[axis]),
+            None => (peak.min(0.0), peak.max(0.0)),
+        };
+        // Invalid regions, and regions that cross zero, are ignored.
+        if start > peak || peak > end || (start < 0.0 && end > 0.0) {
+            continue;
+        }
+        if coord == 0.0 || coord < start || coord > end {
+            return 0.0;
+        }
+        scalar *= if coord < peak {
+            (coord - start) / (peak - start)
+        } else {
+            (end - coord) / (end - peak)
+        };
+    }
+    scalar
+}
+
+/// Interpolates the delta of an untouched coordinate from the two nearest touched points.
+fn interpolate_delta(x: f64, (x1, d1): (f64, f64), (x2, d2): (f64, f64)) -> f64 {
+    if x1 == x2 {
+        return if d1 == d2 { d1 } else { 0.0 };
+    }
+    let ((x1, d1), (x2, d2)) = if x1 < x2 {
+        ((x1, d1), (x2, d2))
+    } else {
+        ((x2, d2), (x1, d1))
+    };
+    if x <= x1 {
+        d1
+    } else if x >= x2 {
+        d2
+    } else {
+        d1 + (x - x1) * (d2 - d1) / (x2 - x1)
+    }
+}
+
+/// Infers the deltas of the points a tuple variation leaves untouched, by interpolating
+/// between the touched points around them in each contour (IUP).
+fn interpolate_untouched(
+    deltas: &mut [Option<Vector2>],
+    points: &[Vector2],
+    contour_ends: &[usize],
+) {
+    let mut start = 0;
+    for &end in contour_ends {
+        let touched: Vec<usize> = (start..end).filter(|&i| deltas[i].is_some()).collect();
+        for (k, &prev) in touched.iter().enumerate() {
+            let next = touched[(k + 1) % touched.len()];
+            let (p1, d1) = (points[prev], deltas[prev].unwrap());
+            let (p2, d2) = (points[next], deltas[next].unwrap());
+            // Walk the untouched points from prev to next, wrapping around the contour.
+            let mut i = prev;
+            loop {
+                i = if i + 1 == end { start } else { i + 1 };
+                if i == next {
+                    break;
+                }
+                let p = points[i];
+                deltas[i] = Some(Vector2::new(
+                    interpolate_delta(p.x, (p1.x, d1.x), (p2.x, d2.x)),
+                    interpolate_delta(p.y, (p1.y, d1.y), (p2.y, d2.y)),
+                ));
+            }
+        }
+        start = end;
+    }
+}
+
+impl<'a> Font<'a> {
+    /// Returns the variation axes of the font, which are empty unless it is a variable font.
+    pub fn variation_axes(&self) -> Result<Vec<VariationAxis>, FontError> {
+        let Some(fvar) = self.table(b"fvar") else {
+            return Ok(Vec::new());
+        };
+        let mut reader = Reader::at(fvar, 4);
+        let axes_offset = reader.u16()? as usize;
+        reader.skip(2);
+        let axis_count = reader.u16()? as usize;
+        let axis_size = reader.u16()? as usize;
+        (0..axis_count)
+            .map(|i| {
+                let mut reader = Reader::at(fvar, axes_offset + i * axis_size);
+                Ok(VariationAxis {
+                    tag: reader.tag()?,
+                    min_value: reader.fixed()?,
+                    default_value: reader.fixed()?,
+                    max_value: reader.fixed()?,
+                    hidden: reader.u16()? & 1 != 0,
+                    name_id: reader.u16()?,
+                })
+            })
+            .collect::<Result<_, FontError>>()
+            .map_err(|_| FontError::InvalidTable(*b"fvar"))
+    }
+
+    /// Selects the instance of a variable font that glyph shapes are built for, by giving the
+    /// values of some of its axes in user units, e.g. `[(*b"wght", 700.0)]`. Values are
+    /// clamped to the axis range, and axes that are not given use their default values.
+    pub fn set_variations(&mut self, variations: &[([u8; 4], f64)]) -> Result<(), FontError> {
+        let axes = self.variation_axes()?;
+        if let Some((tag, _)) = variations
+            .iter()
+            .find(|(tag, _)| !axes.iter().any(|axis| axis.tag == *tag))
+        {
+            return Err(FontError::UnknownAxis(*tag));
+        }
+        let mut coords: Vec<f64> = axes
+            .iter()
+            .map(|axis| {
+                let value = variations
+                    .iter()
+                    .rev()
+                    .find(|(tag, _)| *tag == axis.tag)
+                    .map_or(axis.default_value, |&(_, value)| value);
+                axis.normalize(value)
+            })
+            .collect();
+        if let Some(avar) = self.table(b"avar") {
+            apply_avar(avar, &mut coords).map_err(|_| FontError::InvalidTable(*b"avar"))?;
+        }
+        // Normalized coordinates have the precision of 2.14 fixed-point numbers.
+        for coord in &mut coords {
+            *coord = (coord.clamp(-1.0, 1.0) * 16384.0).round() / 16384.0;
+        }
+        self.coords = if coords.iter().all(|&coord| coord == 0.0) {
+            Vec::new()
+        } else {
+            coords
+        };
+        Ok(())
+    }
+
+    /// Computes the deltas of a glyph's points at the selected instance from the `gvar` table,
+    /// followed by the deltas of its phantom points. `points` are the glyph's points in the
+    /// default instance, which are grouped into contours by `contour_ends`; for a composite
+    /// glyph, they are the offsets of its components, without contours. Returns `None` if the
+    /// glyph does not vary.
+    pub(crate) fn glyph_deltas(
+        &self,
+        glyph: u16,
+        points: &[Vector2],
+        contour_ends: &[usize],
+    ) -> Result<Option<Vec<Vector2>>, FontError> {
+        if self.coords.is_empty() {
+            return Ok(None);
+        }
+        let Some(gvar) = self.table(&GVAR) else {
+            return Ok(None);
+        };
+        self.read_glyph_deltas(gvar, glyph, points, contour_ends)
+            .map_err(|_| FontError::InvalidTable(GVAR))
+    }
+
+    fn read_glyph_deltas(
+        &self,
+        gvar: &[u8],
+        glyph: u16,
+        points: &[Vector2],
+        contour_ends: &[usize],
+    ) -> Result<Option<Vec<Vector2>>, FontError> {
+        let mut reader = Reader::at(gvar, 4);
+        let axis_count = reader.u16()? as usize;
+        let shared_tuple_count = reader.u16()? as usize;
+        let shared_tuples = reader.u32()? as usize;
+        let glyph_count = reader.u16()?;
+        let long_offsets = reader.u16()? & 1 != 0;
+        let data_array = reader.u32()? as usize;
+        if glyph >= glyph_count {
+            return Ok(None);
+        }
+        let (start, end) = if long_offsets {
+            reader.skip(glyph as usize * 4);
+            (reader.u32()? as usize, reader.u32()? as usize)
+        } else {
+            reader.skip(glyph as usize * 2);
+            (reader.u16()? as usize * 2, reader.u16()? as usize * 2)
+        };
+        if start >= end {
+            return Ok(None);
+        }
+        let data = gvar
+            .get(data_array + start..data_array + end)
+            .ok_or(FontError::UnexpectedEnd)?;
+
+        let read_tuple = |reader: &mut Reader| -> Result<Vec<f64>, FontError> {
+            (0..axis_count).map(|_| reader.f2dot14()).collect()
+        };
+        let mut headers = Reader::new(data);
+        let tuple_variation_count = headers.u16()?;
+        let mut serialized = Reader::at(data, headers.u16()? as usize);
+        let shared_points = if tuple_variation_count & SHARED_POINT_NUMBERS != 0 {
+            Some(read_packed_points(&mut serialized)?)
+        } else {
+            None
+        };
+
+        let num_points = points.len() + PHANTOM_POINTS;
+        let mut deltas = vec![Vector2::default(); num_points];
+        for _ in 0..tuple_variation_count & COUNT_MASK {
+            let size = headers.u16()? as usize;
+            let tuple_index = headers.u16()?;
+            let peak = if tuple_index & EMBEDDED_PEAK_TUPLE != 0 {
+                read_tuple(&mut headers)?
+            } else {
+                let index = (tuple_index & TUPLE_INDEX_MASK) as usize;
+                if index >= shared_tuple_count {
+                    return Err(FontError::UnexpectedEnd);
+                }
+                read_tuple(&mut Reader::at(
+                    gvar,
+                    shared_tuples + index * axis_count * 2,
+                ))?
+            };
+            let intermediate = if tuple_index & INTERMEDIATE_REGION != 0 {
+                Some((read_tuple(&mut headers)?, read_tuple(&mut headers)?))
+            } else {
+                None
+            };
+            let mut tuple_data = Reader::new(serialized.bytes(size)?);
+            let intermediate = intermediate
+                .as_ref()
+                .map(|(start, end)| (&start[..], &end[..]));
+            let scalar = tuple_scalar(&self.coords, &peak, intermediate);
+            if scalar == 0.0 {
+                continue;
+            }
+
+            let point_numbers = if tuple_index & PRIVATE_POINT_NUMBERS != 0 {
+                read_packed_points(&mut tuple_data)?
+            } else {
+                shared_points.clone().ok_or(FontError::UnexpectedEnd)?
+            };
+            let count = match &point_numbers {
+                PointNumbers::All => num_points,
+                PointNumbers::List(list) => list.len(),
+            };
+            let xs = read_packed_deltas(&mut tuple_data, count)?;
+            let ys = read_packed_deltas(&mut tuple_data, count)?;
+            match point_numbers {
+                PointNumbers::All => {
+                    for (delta, (x, y)) in deltas.iter_mut().zip(xs.into_iter().zip(ys)) {
+                        *delta += Vector2::new(x, y) * scalar;
+                    }
+                }
+                PointNumbers::List(list) => {
+                    let mut tuple_deltas = vec![None; num_points];
+                    for (&point, (x, y)) in list.iter().zip(xs.into_iter().zip(ys)) {
+                        if let Some(delta) = tuple_deltas.get_mut(point) {
+                            *delta = Some(Vector2::new(x, y));
+                        }
+                    }
+                    interpolate_untouched(&mut tuple_deltas, points, contour_ends);
+                    for (delta, tuple_delta) in deltas.iter_mut().zip(tuple_deltas) {
+                        if let Some(tuple_delta) = tuple_delta {
+                            *delta += tuple_delta * scalar;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Some(deltas))
+    }
+}
+
+/// Remaps normalized coordinates with the piecewise linear segment maps of the `avar` table.
+fn apply_avar(avar: &[u8], coords: &mut [f64]) -> Result<(), FontError> {
+    let mut reader = Reader::at(avar, 6);
+    let axis_count = reader.u16()? as usize;
+    for coord in coords.iter_mut().take(axis_count) {
+        let map_count = reader.u16()? as usize;
+        let mut map = Vec::with_capacity(map_count);
+        for _ in 0..map_count {
+            map.push((reader.f2dot14()?, reader.f2dot14()?));
+        }
+        let Some(upper) = map.iter().position(|&(from, _)| *coord <= from) else {
+            if let Some(&(from, to)) = map.last() {
+                *coord += to - from;
+            }
+            continue;
+        };
+        let (from2, to2) = map[upper];
+        *coord = if upper == 0 || *coord == from2 {
+            *coord + to2 - from2
+        } else {
+            let (from1, to1) = map[upper - 1];
+            to1 + (*coord - from1) * (to2 - to1) / (from2 - from1)
+        };
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::font::tests::{font_data, words};
+    use crate::font_truetype::tests::{triangle, truetype_font_data};
+
+    fn v(x: f64, y: f64) -> Vector2 {
+        Vector2::new(x, y)
+    }
+
+    fn f2dot14(values: &[f64]) -> Vec<u8> {
+        words(
+            &values
+                .iter()
+                .map(|&v| (v * 16384.0) as i32)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn computes_tuple_scalars() {
+        assert_eq!(tuple_scalar(&[0.5], &[1.0], None), 0.5);
+        assert_eq!(tuple_scalar(&[1.0], &[1.0], None), 1.0);
+        assert_eq!(tuple_scalar(&[-0.5], &[1.0], None), 0.0);
+        assert_eq!(tuple_scalar(&[], &[1.0], None), 0.0);
+        assert_eq!(tuple_scalar(&[-0.25], &[-0.5], None), 0.5);
+        // Axes with a peak of 0 don't restrict the region.
+        assert_eq!(tuple_scalar(&[0.5, 0.75], &[0.0, 1.0], None), 0.75);
+        assert_eq!(tuple_scalar(&[0.5, -0.5], &[1.0, -1.0], None), 0.25);
+
+        let region = Some((&[0.25][..], &[1.0][..]));
+        assert_eq!(tuple_scalar(&[0.375], &[0.5], region), 0.5);
+        assert_eq!(tuple_scalar(&[0.5], &[0.5], region), 1.0);
+        assert_eq!(tuple_scalar(&[0.75], &[0.5], region), 0.5);
+        assert_eq!(tuple_scalar(&[0.125], &[0.5], region), 0.0);
+        assert_eq!(tuple_scalar(&[1.0], &[0.5], region), 0.0);
+        // A region that crosses zero is ignored.
+        let region = Some((&[-0.5][..], &[1.0][..]));
+        assert_eq!(tuple_scalar(&[0.25], &[0.5], region), 1.0);
+    }
+
+    fn interpolate(
+        points: &[Vector2],
+        contour_ends: &[usize],
+        touched: &[(usize, Vector2)],
+    ) -> Vec<Option<Vector2>> {
+        let mut deltas = vec![None; points.len()];
+        for &(i, delta) in touched {
+            deltas[i] = Some(delta);
+        }
+        interpolate_untouched(&mut deltas, points, contour_ends);
+        deltas
+    }
+
+    #[test]
+    fn interpolates_untouched_points() {
+        let points = [
+            v(0.0, 0.0),
+            v(50.0, 150.0),
+            v(100.0, 100.0),
+            v(200.0, -50.0),
+            v(75.0, 25.0),
+        ];
+        let deltas = interpolate(&points, &[5], &[(0, v(0.0, 0.0)), (2, v(20.0, 40.0))]);
+        assert_eq!(
+            deltas,
+            [
+                Some(v(0.0, 0.0)),
+                Some(v(10.0, 40.0)),
+                Some(v(20.0, 40.0)),
+                Some(v(20.0, 0.0)),
+                Some(v(15.0, 10.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn shifts_contour_with_one_touched_point() {
+        let points = [
+            v(0.0, 0.0),
+            v(0.0, 100.0),
+            v(100.0, 100.0),
+            v(5.0, 5.0),
+            v(6.0, 6.0),
+        ];
+        let deltas = interpolate(&points, &[3, 5], &[(1, v(10.0, 20.0))]);
+        // The second contour has no touched points, and stays untouched.
+        assert_eq!(
+            deltas,
+            [
+                Some(v(10.0, 20.0)),
+                Some(v(10.0, 20.0)),
+                Some(v(10.0, 20.0)),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn interpolates_between_equal_coordinates() {
+        // Both touched points have x = 0, with different x deltas in the first contour and the
+        // same x delta in the second one.
+        let points = [
+            v(0.0, 0.0),
+            v(50.0, 50.0),
+            v(0.0, 100.0),
+            v(0.0, 0.0),
+            v(50.0, 50.0),
+            v(0.0, 100.0),
+        ];
+        let deltas = interpolate(
+            &points,
+            &[3, 6],
+            &[
+                (0, v(10.0, 0.0)),
+                (2, v(20.0, 0.0)),
+                (3, v(7.0, 0.0)),
+                (5, v(7.0, 10.0)),
+            ],
+        );
+        assert_eq!(deltas[1], Some(v(0.0, 0.0)));
+        assert_eq!(deltas[4], Some(v(7.0, 5.0)));
+    }
+
+    fn packed_points(data: &[u8]) -> Option<Vec<usize>> {
+        match read_packed_points(&mut Reader::new(data)).unwrap() {
+            PointNumbers::All => None,
+            PointNumbers::List(points) => Some(points),
+        }
+    }
+
+    #[test]
+    fn reads_packed_points() {
+        assert_eq!(packed_points(&[0]), None);
+        assert_eq!(packed_points(&[3, 0x02, 1, 2, 3]), Some(vec![1, 3, 6]));
+        assert_eq!(
+            packed_points(&[0x80, 2, 0x81, 0x01, 0x00, 0x00, 0x02]),
+            Some(vec![256, 258])
+        );
+        assert_eq!(
+            packed_points(&[4, 0x01, 5, 1, 0x81, 0x01, 0x00, 0x00, 0x01]),
+            Some(vec![5, 6, 262, 263])
+        );
+        // Runs that go past the count are cut off.
+        assert_eq!(packed_points(&[1, 0x02, 4, 4, 4]), Some(vec![4]));
+        assert!(read_packed_points(&mut Reader::new(&[2, 0x01, 4])).is_err());
+    }
+
+    #[test]
+    fn reads_packed_deltas() {
+        let data = [
+            0x01, 0xFF, 10, 0x80, 0x40, 0x01, 0x00, 0xC0, 0x00, 0x01, 0x00, 0x00,
+        ];
+        assert_eq!(
+            read_packed_deltas(&mut Reader::new(&data), 5).unwrap(),
+            [-1.0, 10.0, 0.0, 256.0, 65536.0]
+        );
+        let mut reader = Reader::new(&[0x83, 0x00, 0xFE]);
+        assert_eq!(read_packed_deltas(&mut reader, 2).unwrap(), [0.0, 0.0]);
+        assert_eq!(read_packed_deltas(&mut reader, 1).unwrap(), [-2.0]);
+        assert!(read_packed_deltas(&mut Reader::new(&[0x41, 0x00, 0x01]), 2).is_err());
+    }
+
+    #[test]
+    fn applies_avar() {
+        let mut avar = words(&[1, 0, 0, 2, 4]);
+        avar.extend(f2dot14(&[-1.0, -1.0, 0.0, 0.0, 0.5, 0.75, 1.0, 1.0]));
+        avar.extend(words(&[0]));
+        let map = |coord: f64| {
+            let mut coords = [coord, coord, coord];
+            apply_avar(&avar, &mut coords).unwrap();
+            // The second axis has no map, and the third is not covered by the table.
+            assert_eq!(coords[1..], [coord, coord]);
+            coords[0]
+        };
+        assert_eq!(map(0.25), 0.375);
+        assert_eq!(map(0.5), 0.75);
+        assert_eq!(map(0.75), 0.875);
+        assert_eq!(map(-0.5), -0.5);
+        assert_eq!(map(1.0), 1.0);
+        assert!(apply_avar(&avar[..12], &mut [0.5]).is_err());
+    }
+
+    /// An `fvar` table with a weight axis from 100 to 900, and a default of 400.
+    fn weight_axis() -> Vec<u8> {
+        let fixed = |value: f64| ((value * 65536.0) as u32).to_be_bytes();
+        let mut fvar = words(&[1, 0, 16, 2, 1, 20, 0, 8]);
+        fvar.extend(b"wght");
+        for value in [100.0, 400.0, 900.0] {
+            fvar.extend(fixed(value));
+        }
+        fvar.extend(words(&[0, 256]));
+        fvar
+    }
+
+    /// The `hhea` and `hmtx` tables of a single glyph with an advance of 500 and a left side
+    /// bearing of 20.
+    fn horizontal_metrics() -> (Vec<u8>, Vec<u8>) {
+        let mut hhea = vec![0; 36];
+        hhea[34..].copy_from_slice(&words(&[1]));
+        (hhea, words(&[500, 20]))
+    }
+
+    fn metrics(font: &Font) -> (u16, i16) {
+        let metrics = font.glyph_metrics(0).unwrap();
+        assert_eq!(font.advance_width(0), Some(metrics.advance_width));
+        assert_eq!(font.left_side_bearing(0), Some(metrics.left_side_bearing));
+        (metrics.advance_width, metrics.left_side_bearing)
+    }
+
+    #[test]
+    fn varies_horizontal_metrics() {
+        // The phantom points of the triangle are moved apart by 40 units at the maximum
+        // weight, with the left one moving left by 10 units.
+        let mut glyph_variations = words(&[1, 10, 8, 0xA000]);
+        glyph_variations.extend(f2dot14(&[1.0]));
+        glyph_variations.extend([2, 0x01, 3, 1, 0x01, 0xF6, 30, 0x81]);
+        let mut gvar = words(&[1, 0, 1, 0, 0, 0, 1, 1, 0, 28, 0, 0]);
+        gvar.extend((glyph_variations.len() as u32).to_be_bytes());
+        gvar.extend(glyph_variations);
+        let (hhea, hmtx) = horizontal_metrics();
+        let data = truetype_font_data(
+            &[triangle()],
+            &[
+                (b"fvar", &weight_axis()),
+                (b"gvar", &gvar),
+                (b"hhea", &hhea),
+                (b"hmtx", &hmtx),
+            ],
+        );
+        let mut font = Font::from_bytes(&data).unwrap();
+        assert_eq!(metrics(&font), (500, 20));
+        font.set_variations(&[(*b"wght", 900.0)]).unwrap();
+        assert_eq!(metrics(&font), (540, 30));
+        font.set_variations(&[(*b"wght", 650.0)]).unwrap();
+        assert_eq!(metrics(&font), (520, 25));
+        font.set_variations(&[(*b"wght", 100.0)]).unwrap();
+        assert_eq!(metrics(&font), (500, 20));
+    }
+
+    #[test]
+    fn keeps_default_metrics_without_truetype_outlines() {
+        let (hhea, hmtx) = horizontal_metrics();
+        let data = font_data(
+            1,
+            &[
+                (b"fvar", &weight_axis()),
+                (b"hhea", &hhea),
+                (b"hmtx", &hmtx),
+            ],
+        );
+        let mut font = Font::from_bytes(&data).unwrap();
+        font.set_variations(&[(*b"wght", 900.0)]).unwrap();
+        assert_eq!(metrics(&font), (500, 20));
+    }
+}
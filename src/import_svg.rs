@@ -0,0 +1,1311 @@
+use crate::{Bounds, Contour, EdgeColor, EdgeHolder, FillRule, Shape, Vector2};
+use std::borrow::Cow;
+use std::f64::consts::PI;
+use std::fmt;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SvgError {
+    /// Malformed path data, at the given byte offset into the path string.
+    InvalidPathData { offset: usize },
+    /// Malformed XML, at the given byte offset into the document.
+    InvalidXml { offset: usize },
+    /// The document's root element is not `<svg>`.
+    MissingSvgElement,
+    /// An attribute with an invalid or unsupported value, at the given byte offset into the
+    /// document.
+    InvalidAttribute { name: String, offset: usize },
+}
+
+impl fmt::Display for SvgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SvgError::InvalidPathData { offset } => {
+                write!(f, "invalid path data at offset {offset}")
+            }
+            SvgError::InvalidXml { offset } => write!(f, "invalid XML at offset {offset}"),
+            SvgError::MissingSvgElement => write!(f, "missing <svg> root element"),
+            SvgError::InvalidAttribute { name, offset } => {
+                write!(f, "invalid value for attribute `{name}` at offset {offset}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SvgError {}
+
+/// An affine transformation `[a, b, c, d, e, f]`, which maps `(x, y)` to
+/// `(a * x + c * y + e, b * x + d * y + f)` like SVG's `matrix()`.
+#[derive(Clone, Copy, Debug)]
+struct Transform([f64; 6]);
+
+impl Transform {
+    const IDENTITY: Transform = Transform([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+    fn translate(t: Vector2) -> Transform {
+        Transform([1.0, 0.0, 0.0, 1.0, t.x, t.y])
+    }
+
+    fn apply(&self, p: Vector2) -> Vector2 {
+        let [a, b, c, d, e, f] = self.0;
+        Vector2::new(a * p.x + c * p.y + e, b * p.x + d * p.y + f)
+    }
+
+    /// Returns the transformation that applies `other` first, then `self`.
+    fn multiply(&self, other: &Transform) -> Transform {
+        let [a, b, c, d, e, f] = self.0;
+        let [a2, b2, c2, d2, e2, f2] = other.0;
+        Transform([
+            a * a2 + c * b2,
+            b * a2 + d * b2,
+            a * c2 + c * d2,
+            b * c2 + d * d2,
+            a * e2 + c * f2 + e,
+            b * e2 + d * f2 + f,
+        ])
+    }
+}
+
+struct PathParser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> PathParser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input: input.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn error(&self) -> SvgError {
+        SvgError::InvalidPathData { offset: self.pos }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.input.len() && self.input[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips whitespace and at most one comma.
+    fn skip_separator(&mut self) {
+        self.skip_whitespace();
+        if self.input.get(self.pos) == Some(&b',') {
+            self.pos += 1;
+            self.skip_whitespace();
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.input.get(self.pos).copied()
+    }
+
+    /// Returns true if the next token is a number, i.e. an implicitly repeated command follows.
+    fn at_number(&mut self) -> bool {
+        self.peek()
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, b'+' | b'-' | b'.'))
+    }
+
+    fn number(&mut self) -> Result<f64, SvgError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let digits = |parser: &mut Self| {
+            let from = parser.pos;
+            while parser.pos < parser.input.len() && parser.input[parser.pos].is_ascii_digit() {
+                parser.pos += 1;
+            }
+            parser.pos > from
+        };
+        if matches!(self.input.get(self.pos), Some(b'+' | b'-')) {
+            self.pos += 1;
+        }
+        let mut valid = digits(self);
+        if self.input.get(self.pos) == Some(&b'.') {
+            self.pos += 1;
+            valid |= digits(self);
+        }
+        if !valid {
+            self.pos = start;
+            return Err(self.error());
+        }
+        if matches!(self.input.get(self.pos), Some(b'e' | b'E')) {
+            let mantissa_end = self.pos;
+            self.pos += 1;
+            if matches!(self.input.get(self.pos), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            if !digits(self) {
+                self.pos = mantissa_end;
+            }
+        }
+        let text = std::str::from_utf8(&self.input[start..self.pos]).unwrap();
+        let n = text
+            .parse()
+            .map_err(|_| SvgError::InvalidPathData { offset: start })?;
+        self.skip_separator();
+        Ok(n)
+    }
+
+    /// Parses an arc flag, which may be immediately followed by the next argument.
+    fn flag(&mut self) -> Result<bool, SvgError> {
+        let flag = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(self.error()),
+        };
+        self.pos += 1;
+        self.skip_separator();
+        Ok(flag)
+    }
+
+    fn point(&mut self) -> Result<Vector2, SvgError> {
+        let x = self.number()?;
+        let y = self.number()?;
+        Ok(Vector2::new(x, y))
+    }
+}
+
+/// Accumulates the edges of the current subpath, and adds finished subpaths to the shape.
+/// Points are given in the path's own coordinate system, and mapped through `transform` when
+/// the edges are created.
+struct PathBuilder<'a> {
+    shape: &'a mut Shape,
+    transform: Transform,
+    contour: Contour,
+    start: Vector2,
+    current: Vector2,
+}
+
+impl<'a> PathBuilder<'a> {
+    fn new(shape: &'a mut Shape, transform: Transform) -> Self {
+        Self {
+            shape,
+            transform,
+            contour: Contour::default(),
+            start: Vector2::default(),
+            current: Vector2::default(),
+        }
+    }
+
+    fn line_to(&mut self, p: Vector2) {
+        if p != self.current {
+            let t = &self.transform;
+            self.contour.add_edge(EdgeHolder::linear(
+                t.apply(self.current),
+                t.apply(p),
+                EdgeColor::White,
+            ));
+        }
+        self.current = p;
+    }
+
+    fn quadratic_to(&mut self, p1: Vector2, p2: Vector2) {
+        let t = &self.transform;
+        self.contour.add_edge(EdgeHolder::quadratic(
+            t.apply(self.current),
+            t.apply(p1),
+            t.apply(p2),
+            EdgeColor::White,
+        ));
+        self.current = p2;
+    }
+
+    fn cubic_to(&mut self, p1: Vector2, p2: Vector2, p3: Vector2) {
+        let t = &self.transform;
+        self.contour.add_edge(EdgeHolder::cubic(
+            t.apply(self.current),
+            t.apply(p1),
+            t.apply(p2),
+            t.apply(p3),
+            EdgeColor::White,
+        ));
+        self.current = p3;
+    }
+
+    /// Approximates an elliptical arc with cubic segments spanning at most 90 degrees each,
+    /// following the endpoint to center parameterization of the SVG specification.
+    fn arc_to(&mut self, radius: Vector2, rotation: f64, large_arc: bool, sweep: bool, p: Vector2) {
+        let p0 = self.current;
+        if p == p0 {
+            return;
+        }
+        let (mut rx, mut ry) = (radius.x.abs(), radius.y.abs());
+        if rx == 0.0 || ry == 0.0 {
+            self.line_to(p);
+            return;
+        }
+        let (sin, cos) = (rotation * PI / 180.0).sin_cos();
+        let half = (p0 - p) * 0.5;
+        let x1 = cos * half.x + sin * half.y;
+        let y1 = -sin * half.x + cos * half.y;
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1.0 {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+        let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let mut coef = (num / den).max(0.0).sqrt();
+        if large_arc == sweep {
+            coef = -coef;
+        }
+        let cx1 = coef * rx * y1 / ry;
+        let cy1 = -coef * ry * x1 / rx;
+        let mid = (p0 + p) * 0.5;
+        let center = Vector2::new(cos * cx1 - sin * cy1 + mid.x, sin * cx1 + cos * cy1 + mid.y);
+        let angle = |u: Vector2, v: Vector2| (u.x * v.y - u.y * v.x).atan2(u.x * v.x + u.y * v.y);
+        let u = Vector2::new((x1 - cx1) / rx, (y1 - cy1) / ry);
+        let v = Vector2::new((-x1 - cx1) / rx, (-y1 - cy1) / ry);
+        let theta = angle(Vector2::new(1.0, 0.0), u);
+        let mut delta = angle(u, v);
+        if !sweep && delta > 0.0 {
+            delta -= 2.0 * PI;
+        } else if sweep && delta < 0.0 {
+            delta += 2.0 * PI;
+        }
+        let point = |t: f64| {
+            let (s, c) = t.sin_cos();
+            center + Vector2::new(rx * c * cos - ry * s * sin, rx * c * sin + ry * s * cos)
+        };
+        let derivative = |t: f64| {
+            let (s, c) = t.sin_cos();
+            Vector2::new(-rx * s * cos - ry * c * sin, -rx * s * sin + ry * c * cos)
+        };
+        let segments = (delta.abs() / (0.5 * PI)).ceil().max(1.0) as usize;
+        let step = delta / segments as f64;
+        let k = 4.0 / 3.0 * (0.25 * step).tan();
+        for i in 0..segments {
+            let t0 = theta + i as f64 * step;
+            let t1 = t0 + step;
+            let end = if i + 1 == segments { p } else { point(t1) };
+            self.cubic_to(
+                self.current + derivative(t0) * k,
+                end - derivative(t1) * k,
+                end,
+            );
+        }
+    }
+
+    /// Closes the current subpath with a line back to its start, and begins a new one.
+    fn close(&mut self) {
+        self.line_to(self.start);
+        let contour = std::mem::take(&mut self.contour);
+        if !contour.edges.is_empty() {
+            self.shape.add_contour(contour);
+        }
+    }
+
+    fn move_to(&mut self, p: Vector2) {
+        self.close();
+        self.start = p;
+        self.current = p;
+    }
+
+    /// Adds a rectangle with corners rounded by the radii `r`, traced in the same direction as
+    /// the SVG specification's equivalent path.
+    fn rect(&mut self, p: Vector2, size: Vector2, r: Vector2) {
+        let (x0, y0, x1, y1) = (p.x, p.y, p.x + size.x, p.y + size.y);
+        self.move_to(Vector2::new(x0 + r.x, y0));
+        self.line_to(Vector2::new(x1 - r.x, y0));
+        self.arc_to(r, 0.0, false, true, Vector2::new(x1, y0 + r.y));
+        self.line_to(Vector2::new(x1, y1 - r.y));
+        self.arc_to(r, 0.0, false, true, Vector2::new(x1 - r.x, y1));
+        self.line_to(Vector2::new(x0 + r.x, y1));
+        self.arc_to(r, 0.0, false, true, Vector2::new(x0, y1 - r.y));
+        self.line_to(Vector2::new(x0, y0 + r.y));
+        self.arc_to(r, 0.0, false, true, Vector2::new(x0 + r.x, y0));
+        self.close();
+    }
+
+    /// Adds an axis-aligned ellipse as four quarter arcs, starting at its rightmost point.
+    fn ellipse(&mut self, center: Vector2, r: Vector2) {
+        self.move_to(center + Vector2::new(r.x, 0.0));
+        self.arc_to(r, 0.0, false, true, center + Vector2::new(0.0, r.y));
+        self.arc_to(r, 0.0, false, true, center + Vector2::new(-r.x, 0.0));
+        self.arc_to(r, 0.0, false, true, center + Vector2::new(0.0, -r.y));
+        self.arc_to(r, 0.0, false, true, center + Vector2::new(r.x, 0.0));
+        self.close();
+    }
+
+    fn polygon(&mut self, points: &[Vector2]) {
+        if let Some((&first, rest)) = points.split_first() {
+            self.move_to(first);
+            for &p in rest {
+                self.line_to(p);
+            }
+            self.close();
+        }
+    }
+}
+
+/// Parses SVG path data and adds its subpaths to `shape`, mapped through `transform`.
+fn append_path(shape: &mut Shape, d: &str, transform: Transform) -> Result<(), SvgError> {
+    let mut parser = PathParser::new(d);
+    let mut builder = PathBuilder::new(shape, transform);
+    // The second control point of the previous cubic or the control point of the previous
+    // quadratic, for the reflection in the smooth curve commands.
+    let mut prev_cubic_control = None;
+    let mut prev_quadratic_control = None;
+    let mut command = None;
+    while let Some(c) = parser.peek() {
+        if c.is_ascii_alphabetic() {
+            parser.pos += 1;
+            command = Some(c);
+        } else if command.is_none() || !parser.at_number() {
+            return Err(parser.error());
+        }
+        let cmd = command.unwrap();
+        let relative = cmd.is_ascii_lowercase();
+        let origin = if relative {
+            builder.current
+        } else {
+            Vector2::default()
+        };
+        let mut cubic_control = None;
+        let mut quadratic_control = None;
+        match cmd.to_ascii_uppercase() {
+            b'M' => {
+                builder.move_to(origin + parser.point()?);
+                // Subsequent coordinate pairs are implicit line commands.
+                command = Some(if relative { b'l' } else { b'L' });
+            }
+            b'L' => builder.line_to(origin + parser.point()?),
+            b'H' => {
+                let x = parser.number()?;
+                let y = builder.current.y;
+                builder.line_to(Vector2::new(origin.x + x, y));
+            }
+            b'V' => {
+                let y = parser.number()?;
+                let x = builder.current.x;
+                builder.line_to(Vector2::new(x, origin.y + y));
+            }
+            b'C' => {
+                let p1 = origin + parser.point()?;
+                let p2 = origin + parser.point()?;
+                let p3 = origin + parser.point()?;
+                builder.cubic_to(p1, p2, p3);
+                cubic_control = Some(p2);
+            }
+            b'S' => {
+                let p1 = prev_cubic_control.map_or(builder.current, |c| builder.current * 2.0 - c);
+                let p2 = origin + parser.point()?;
+                let p3 = origin + parser.point()?;
+                builder.cubic_to(p1, p2, p3);
+                cubic_control = Some(p2);
+            }
+            b'Q' => {
+                let p1 = origin + parser.point()?;
+                let p2 = origin + parser.point()?;
+                builder.quadratic_to(p1, p2);
+                quadratic_control = Some(p1);
+            }
+            b'T' => {
+                let p1 =
+                    prev_quadratic_control.map_or(builder.current, |c| builder.current * 2.0 - c);
+                let p2 = origin + parser.point()?;
+                builder.quadratic_to(p1, p2);
+                quadratic_control = Some(p1);
+            }
+            b'A' => {
+                let radius = parser.point()?;
+                let rotation = parser.number()?;
+                let large_arc = parser.flag()?;
+                let sweep = parser.flag()?;
+                let p = origin + parser.point()?;
+                builder.arc_to(radius, rotation, large_arc, sweep, p);
+            }
+            b'Z' => {
+                builder.close();
+                builder.current = builder.start;
+                command = None;
+            }
+            _ => {
+                parser.pos -= 1;
+                return Err(parser.error());
+            }
+        }
+        prev_cubic_control = cubic_control;
+        prev_quadratic_control = quadratic_control;
+    }
+    builder.close();
+    Ok(())
+}
+
+impl Shape {
+    /// Builds a shape from SVG path data, i.e. the contents of a `<path>` element's `d`
+    /// attribute. Every subpath becomes a contour, and open subpaths are closed with a straight
+    /// line. Since SVG coordinates are y-down, the shape's `inverse_y_axis` is set.
+    pub fn from_svg_path(d: &str) -> Result<Shape, SvgError> {
+        let mut shape = Shape {
+            inverse_y_axis: true,
+            ..Default::default()
+        };
+        append_path(&mut shape, d, Transform::IDENTITY)?;
+        Ok(shape)
+    }
+}
+
+struct Attribute<'a> {
+    name: &'a str,
+    value: Cow<'a, str>,
+    offset: usize,
+}
+
+impl<'a> Attribute<'a> {
+    fn error(&self) -> SvgError {
+        SvgError::InvalidAttribute {
+            name: self.name.to_string(),
+            offset: self.offset,
+        }
+    }
+}
+
+enum XmlToken<'a> {
+    Start {
+        name: &'a str,
+        attributes: Vec<Attribute<'a>>,
+        empty: bool,
+    },
+    End {
+        name: &'a str,
+        offset: usize,
+    },
+}
+
+/// A minimal XML reader, which yields element start and end tags and skips everything else.
+struct XmlReader<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> XmlReader<'a> {
+    fn error(&self) -> SvgError {
+        SvgError::InvalidXml { offset: self.pos }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if self.rest().starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_past(&mut self, terminator: &str) -> Result<(), SvgError> {
+        match self.rest().find(terminator) {
+            Some(i) => {
+                self.pos += i + terminator.len();
+                Ok(())
+            }
+            None => Err(SvgError::InvalidXml {
+                offset: self.input.len(),
+            }),
+        }
+    }
+
+    /// Skips a `<!DOCTYPE ...>` or similar declaration, which may contain an internal subset
+    /// in brackets and quoted strings.
+    fn skip_declaration(&mut self) -> Result<(), SvgError> {
+        let mut depth = 0;
+        let mut quote = None;
+        for (i, c) in self.rest().char_indices() {
+            match (quote, c) {
+                (Some(q), _) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, '"' | '\'') => quote = Some(c),
+                (None, '[') => depth += 1,
+                (None, ']') => depth -= 1,
+                (None, '>') if depth == 0 => {
+                    self.pos += i + 1;
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+        Err(SvgError::InvalidXml {
+            offset: self.input.len(),
+        })
+    }
+
+    fn name(&mut self) -> Result<&'a str, SvgError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | ':' | '-' | '.')))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error());
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    /// Replaces the predefined entities and character references in an attribute value.
+    fn unescape(&self, raw: &'a str, offset: usize) -> Result<Cow<'a, str>, SvgError> {
+        if !raw.contains('&') {
+            return Ok(Cow::Borrowed(raw));
+        }
+        let mut value = String::with_capacity(raw.len());
+        let mut rest = raw;
+        while let Some(i) = rest.find('&') {
+            value.push_str(&rest[..i]);
+            let error = SvgError::InvalidXml {
+                offset: offset + (raw.len() - rest.len()) + i,
+            };
+            let end = rest[i..].find(';').ok_or(error.clone())?;
+            let entity = &rest[i + 1..i + end];
+            let c = match entity {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                _ => {
+                    let code = if let Some(hex) = entity.strip_prefix("#x") {
+                        u32::from_str_radix(hex, 16).ok()
+                    } else if let Some(dec) = entity.strip_prefix('#') {
+                        dec.parse().ok()
+                    } else {
+                        None
+                    };
+                    code.and_then(char::from_u32).ok_or(error)?
+                }
+            };
+            value.push(c);
+            rest = &rest[i + end + 1..];
+        }
+        value.push_str(rest);
+        Ok(Cow::Owned(value))
+    }
+
+    fn start_tag(&mut self) -> Result<XmlToken<'a>, SvgError> {
+        let name = self.name()?;
+        let mut attributes = Vec::new();
+        loop {
+            let separated = self.rest().starts_with(char::is_whitespace);
+            self.skip_whitespace();
+            if self.eat("/>") {
+                return Ok(XmlToken::Start {
+                    name,
+                    attributes,
+                    empty: true,
+                });
+            }
+            if self.eat(">") {
+                return Ok(XmlToken::Start {
+                    name,
+                    attributes,
+                    empty: false,
+                });
+            }
+            if !separated {
+                return Err(self.error());
+            }
+            let attribute_offset = self.pos;
+            let attribute_name = self.name()?;
+            self.skip_whitespace();
+            if !self.eat("=") {
+                return Err(self.error());
+            }
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(q @ ('"' | '\'')) => q,
+                _ => return Err(self.error()),
+            };
+            self.pos += 1;
+            let value_offset = self.pos;
+            let len = self.rest().find(quote).ok_or(SvgError::InvalidXml {
+                offset: self.input.len(),
+            })?;
+            let raw = &self.rest()[..len];
+            self.pos += len + 1;
+            attributes.push(Attribute {
+                name: attribute_name,
+                value: self.unescape(raw, value_offset)?,
+                offset: attribute_offset,
+            });
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<XmlToken<'a>>, SvgError> {
+        loop {
+            match self.rest().find('<') {
+                Some(i) => self.pos += i,
+                None => {
+                    self.pos = self.input.len();
+                    return Ok(None);
+                }
+            }
+            let offset = self.pos;
+            if self.eat("<!--") {
+                self.skip_past("-->")?;
+            } else if self.eat("<![CDATA[") {
+                self.skip_past("]]>")?;
+            } else if self.eat("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!") {
+                self.skip_declaration()?;
+            } else if self.eat("</") {
+                let name = self.name()?;
+                self.skip_whitespace();
+                if !self.eat(">") {
+                    return Err(self.error());
+                }
+                return Ok(Some(XmlToken::End { name, offset }));
+            } else {
+                self.pos += 1;
+                return self.start_tag().map(Some);
+            }
+        }
+    }
+}
+
+/// Returns the name without its namespace prefix.
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap()
+}
+
+fn attribute<'b, 'a>(attributes: &'b [Attribute<'a>], name: &str) -> Option<&'b Attribute<'a>> {
+    attributes.iter().find(|a| a.name == name)
+}
+
+/// Parses a list of numbers separated by whitespace and/or commas.
+fn parse_numbers(attribute: &Attribute) -> Result<Vec<f64>, SvgError> {
+    let mut parser = PathParser::new(&attribute.value);
+    parser.skip_whitespace();
+    let mut numbers = Vec::new();
+    while parser.peek().is_some() {
+        numbers.push(parser.number().map_err(|_| attribute.error())?);
+    }
+    Ok(numbers)
+}
+
+/// Parses a length in user units. Absolute units are converted at 96 pixels per inch, and
+/// relative units such as percentages are not supported.
+fn parse_length(attribute: &Attribute) -> Result<f64, SvgError> {
+    let value = attribute.value.trim();
+    let unit_start = value
+        .rfind(|c: char| c.is_ascii_digit() || c == '.')
+        .map_or(0, |i| i + 1);
+    let (number, unit) = value.split_at(unit_start);
+    let scale = match unit {
+        "" | "px" => 1.0,
+        "in" => 96.0,
+        "cm" => 96.0 / 2.54,
+        "mm" => 96.0 / 25.4,
+        "pt" => 96.0 / 72.0,
+        "pc" => 16.0,
+        _ => return Err(attribute.error()),
+    };
+    let number: f64 = number.parse().map_err(|_| attribute.error())?;
+    if !number.is_finite() {
+        return Err(attribute.error());
+    }
+    Ok(number * scale)
+}
+
+fn length(attributes: &[Attribute], name: &str) -> Result<Option<f64>, SvgError> {
+    attribute(attributes, name).map(parse_length).transpose()
+}
+
+/// Parses a `transform` attribute, i.e. a list of `matrix`, `translate`, `scale`, `rotate`,
+/// `skewX` and `skewY` functions.
+fn parse_transform(attribute: &Attribute) -> Result<Transform, SvgError> {
+    let mut parser = PathParser::new(&attribute.value);
+    let mut transform = Transform::IDENTITY;
+    while parser.peek().is_some() {
+        let start = parser.pos;
+        while parser.pos < parser.input.len() && parser.input[parser.pos].is_ascii_alphabetic() {
+            parser.pos += 1;
+        }
+        let name = &parser.input[start..parser.pos];
+        if parser.peek() != Some(b'(') {
+            return Err(attribute.error());
+        }
+        parser.pos += 1;
+        let mut args = Vec::with_capacity(6);
+        while parser.peek() != Some(b')') {
+            args.push(parser.number().map_err(|_| attribute.error())?);
+        }
+        parser.pos += 1;
+        parser.skip_separator();
+        let m = match (name, &args[..]) {
+            (b"matrix", &[a, b, c, d, e, f]) => Transform([a, b, c, d, e, f]),
+            (b"translate", &[tx]) => Transform::translate(Vector2::new(tx, 0.0)),
+            (b"translate", &[tx, ty]) => Transform::translate(Vector2::new(tx, ty)),
+            (b"scale", &[s]) => Transform([s, 0.0, 0.0, s, 0.0, 0.0]),
+            (b"scale", &[sx, sy]) => Transform([sx, 0.0, 0.0, sy, 0.0, 0.0]),
+            (b"rotate", &[angle, ..]) => {
+                let (sin, cos) = (angle * PI / 180.0).sin_cos();
+                let rotation = Transform([cos, sin, -sin, cos, 0.0, 0.0]);
+                match &args[1..] {
+                    [] => rotation,
+                    &[cx, cy] => {
+                        let c = Vector2::new(cx, cy);
+                        Transform::translate(c)
+                            .multiply(&rotation)
+                            .multiply(&Transform::translate(-c))
+                    }
+                    _ => return Err(attribute.error()),
+                }
+            }
+            (b"skewX", &[angle]) => {
+                Transform([1.0, 0.0, (angle * PI / 180.0).tan(), 1.0, 0.0, 0.0])
+            }
+            (b"skewY", &[angle]) => {
+                Transform([1.0, (angle * PI / 180.0).tan(), 0.0, 1.0, 0.0, 0.0])
+            }
+            _ => return Err(attribute.error()),
+        };
+        transform = transform.multiply(&m);
+    }
+    Ok(transform)
+}
+
+fn element_transform(attributes: &[Attribute]) -> Result<Transform, SvgError> {
+    attribute(attributes, "transform").map_or(Ok(Transform::IDENTITY), parse_transform)
+}
+
+/// Returns the element's own fill rule, if it specifies one. A declaration in the `style`
+/// attribute takes precedence over the `fill-rule` attribute.
+fn element_fill_rule(attributes: &[Attribute]) -> Result<Option<FillRule>, SvgError> {
+    let parse = |attribute: &Attribute, value: &str| match value.trim() {
+        "nonzero" => Ok(Some(FillRule::NonZero)),
+        "evenodd" => Ok(Some(FillRule::Odd)),
+        "inherit" => Ok(None),
+        _ => Err(attribute.error()),
+    };
+    if let Some(style) = attribute(attributes, "style") {
+        for declaration in style.value.split(';') {
+            if let Some((property, value)) = declaration.split_once(':') {
+                if property.trim() == "fill-rule" {
+                    return parse(style, value);
+                }
+            }
+        }
+    }
+    match attribute(attributes, "fill-rule") {
+        Some(fill_rule) => parse(fill_rule, &fill_rule.value),
+        None => Ok(None),
+    }
+}
+
+/// Computes the transformation from an `<svg>` element's user space to its viewport, placed
+/// at `origin`, along with the viewport's bounds in the parent's coordinates if they are
+/// known. Percentage sizes are treated as unspecified.
+fn viewport_transform(
+    attributes: &[Attribute],
+    origin: Vector2,
+) -> Result<(Transform, Option<Bounds>), SvgError> {
+    let size = |name| match attribute(attributes, name) {
+        Some(a) if a.value.trim_end().ends_with('%') => Ok(None),
+        Some(a) => parse_length(a).map(Some),
+        None => Ok(None),
+    };
+    let (width, height) = (size("width")?, size("height")?);
+    let bounds = |w: f64, h: f64| Bounds {
+        l: origin.x,
+        b: origin.y,
+        r: origin.x + w,
+        t: origin.y + h,
+    };
+    let view_box = match attribute(attributes, "viewBox") {
+        Some(a) => match parse_numbers(a)?[..] {
+            [x, y, w, h] if w > 0.0 && h > 0.0 => (x, y, w, h),
+            _ => return Err(a.error()),
+        },
+        None => {
+            let viewport = width.zip(height).map(|(w, h)| bounds(w, h));
+            return Ok((Transform::translate(origin), viewport));
+        }
+    };
+    let (vx, vy, vw, vh) = view_box;
+    let (w, h) = match (width, height) {
+        (Some(w), Some(h)) => (w, h),
+        (Some(w), None) => (w, w * vh / vw),
+        (None, Some(h)) => (h * vw / vh, h),
+        (None, None) => {
+            let viewport = Bounds {
+                l: origin.x + vx,
+                b: origin.y + vy,
+                r: origin.x + vx + vw,
+                t: origin.y + vy + vh,
+            };
+            return Ok((Transform::translate(origin), Some(viewport)));
+        }
+    };
+    // The alignment of the view box within the viewport along each axis, from 0 to 1, and
+    // whether it's scaled to cover the viewport rather than fit inside it.
+    let mut align = Some((0.5, 0.5));
+    let mut slice = false;
+    if let Some(a) = attribute(attributes, "preserveAspectRatio") {
+        let mut words = a.value.split_whitespace().peekable();
+        words.next_if_eq(&"defer");
+        let position = |s: &str| match s {
+            "Min" => Some(0.0),
+            "Mid" => Some(0.5),
+            "Max" => Some(1.0),
+            _ => None,
+        };
+        align = match words.next() {
+            Some("none") => None,
+            Some(word) if word.len() == 8 && word.is_ascii() && word.starts_with('x') => {
+                let x = position(&word[1..4]);
+                let y = position(&word[5..8]).filter(|_| &word[4..5] == "Y");
+                Some(x.zip(y).ok_or(a.error())?)
+            }
+            _ => return Err(a.error()),
+        };
+        slice = match words.next() {
+            None | Some("meet") => false,
+            Some("slice") => true,
+            _ => return Err(a.error()),
+        };
+        if words.next().is_some() {
+            return Err(a.error());
+        }
+    }
+    let (mut sx, mut sy) = (w / vw, h / vh);
+    let mut t = origin - Vector2::new(vx * sx, vy * sy);
+    if let Some((ax, ay)) = align {
+        let s = if slice { sx.max(sy) } else { sx.min(sy) };
+        (sx, sy) = (s, s);
+        t = origin - Vector2::new(vx * s, vy * s);
+        t += Vector2::new(ax * (w - vw * s), ay * (h - vh * s));
+    }
+    Ok((Transform([sx, 0.0, 0.0, sy, t.x, t.y]), Some(bounds(w, h))))
+}
+
+/// Adds the geometry of a basic shape or path element to `shape`. Elements that are not
+/// rendered because of a zero size are skipped.
+fn append_element(
+    shape: &mut Shape,
+    name: &str,
+    attributes: &[Attribute],
+    transform: Transform,
+) -> Result<(), SvgError> {
+    let get = |name| length(attributes, name).map(|l| l.unwrap_or(0.0));
+    if name == "path" {
+        return match attribute(attributes, "d") {
+            Some(d) => append_path(shape, &d.value, transform).map_err(|_| d.error()),
+            None => Ok(()),
+        };
+    }
+    let mut builder = PathBuilder::new(shape, transform);
+    match name {
+        "rect" => {
+            let p = Vector2::new(get("x")?, get("y")?);
+            let size = Vector2::new(get("width")?, get("height")?);
+            if size.x <= 0.0 || size.y <= 0.0 {
+                return Ok(());
+            }
+            // A missing radius defaults to the other one.
+            let (rx, ry) = (length(attributes, "rx")?, length(attributes, "ry")?);
+            let r = Vector2::new(
+                rx.or(ry).unwrap_or(0.0).abs().min(0.5 * size.x),
+                ry.or(rx).unwrap_or(0.0).abs().min(0.5 * size.y),
+            );
+            builder.rect(p, size, r);
+        }
+        "circle" | "ellipse" => {
+            let center = Vector2::new(get("cx")?, get("cy")?);
+            let r = if name == "circle" {
+                Vector2::from(get("r")?)
+            } else {
+                Vector2::new(get("rx")?, get("ry")?)
+            };
+            if r.x > 0.0 && r.y > 0.0 {
+                builder.ellipse(center, r);
+            }
+        }
+        "polygon" | "polyline" => {
+            if let Some(a) = attribute(attributes, "points") {
+                let numbers = parse_numbers(a)?;
+                if numbers.len() % 2 != 0 {
+                    return Err(a.error());
+                }
+                let points: Vec<Vector2> = numbers
+                    .chunks_exact(2)
+                    .map(|p| Vector2::new(p[0], p[1]))
+                    .collect();
+                builder.polygon(&points);
+            }
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+/// A shape imported from an SVG document by [`load_svg_shape`].
+pub struct SvgShape {
+    pub shape: Shape,
+    /// The fill rule of the document's shapes, which should be passed along wherever the shape
+    /// is filled, e.g. [`distance_sign_correction`](crate::distance_sign_correction). Since a
+    /// shape has only one fill rule, the first element's is used.
+    pub fill_rule: FillRule,
+    /// The document's viewport in shape coordinates, if its size or `viewBox` is specified.
+    pub view_box: Option<Bounds>,
+}
+
+/// The inherited state of the element being walked.
+struct Group<'a> {
+    name: &'a str,
+    transform: Transform,
+    fill_rule: FillRule,
+    rendered: bool,
+}
+
+/// Loads the shapes of an SVG document into a single shape. The `<path>`, `<rect>`,
+/// `<circle>`, `<ellipse>`, `<polygon>` and `<polyline>` elements are imported, including
+/// those nested in `<g>` and `<svg>` elements, with their `transform` attributes applied.
+/// If the root element has both a `viewBox` and a size, coordinates are mapped from the view
+/// box to the viewport. The contents of other elements, such as `<defs>` and `<clipPath>`, are
+/// ignored, as are styling properties besides `fill-rule`. Since SVG coordinates are y-down,
+/// the shape's `inverse_y_axis` is set.
+pub fn load_svg_shape(svg: &str) -> Result<SvgShape, SvgError> {
+    let mut reader = XmlReader { input: svg, pos: 0 };
+    let (name, attributes, empty) = match reader.next_token()? {
+        Some(XmlToken::Start {
+            name,
+            attributes,
+            empty,
+        }) if local_name(name) == "svg" => (name, attributes, empty),
+        _ => return Err(SvgError::MissingSvgElement),
+    };
+    let mut shape = Shape {
+        inverse_y_axis: true,
+        ..Default::default()
+    };
+    // Coordinates on the root element have no effect.
+    let (transform, view_box) = viewport_transform(&attributes, Vector2::default())?;
+    let mut stack = vec![Group {
+        name,
+        transform: transform.multiply(&element_transform(&attributes)?),
+        fill_rule: element_fill_rule(&attributes)?.unwrap_or_default(),
+        rendered: true,
+    }];
+    let mut fill_rule = None;
+    while !empty && !stack.is_empty() {
+        match reader.next_token()? {
+            None => return Err(reader.error()),
+            Some(XmlToken::Start {
+                name,
+                attributes,
+                empty,
+            }) => {
+                let parent = stack.last().unwrap();
+                let mut group = Group {
+                    name,
+                    transform: parent.transform,
+                    fill_rule: parent.fill_rule,
+                    rendered: false,
+                };
+                if parent.rendered {
+                    let element = local_name(name);
+                    if let Some(rule) = element_fill_rule(&attributes)? {
+                        group.fill_rule = rule;
+                    }
+                    match element {
+                        "g" | "svg" => {
+                            if element == "svg" {
+                                let x = length(&attributes, "x")?.unwrap_or(0.0);
+                                let y = length(&attributes, "y")?.unwrap_or(0.0);
+                                let (viewport, _) =
+                                    viewport_transform(&attributes, Vector2::new(x, y))?;
+                                group.transform = group.transform.multiply(&viewport);
+                            }
+                            group.transform =
+                                group.transform.multiply(&element_transform(&attributes)?);
+                            group.rendered = true;
+                        }
+                        "path" | "rect" | "circle" | "ellipse" | "polygon" | "polyline" => {
+                            let transform =
+                                group.transform.multiply(&element_transform(&attributes)?);
+                            append_element(&mut shape, element, &attributes, transform)?;
+                            fill_rule.get_or_insert(group.fill_rule);
+                        }
+                        _ => {}
+                    }
+                }
+                if !empty {
+                    stack.push(group);
+                }
+            }
+            Some(XmlToken::End { name, offset }) => {
+                if stack.pop().unwrap().name != name {
+                    return Err(SvgError::InvalidXml { offset });
+                }
+            }
+        }
+    }
+    Ok(SvgShape {
+        shape,
+        fill_rule: fill_rule.unwrap_or_default(),
+        view_box,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicSegment, LinearSegment, QuadraticSegment, Segment};
+
+    fn v(x: f64, y: f64) -> Vector2 {
+        Vector2::new(x, y)
+    }
+
+    /// The points of each edge of each contour.
+    fn contours(shape: &Shape) -> Vec<Vec<Vec<Vector2>>> {
+        shape
+            .contours
+            .iter()
+            .map(|contour| {
+                contour
+                    .edges
+                    .iter()
+                    .map(|edge| match edge.segment {
+                        Segment::Linear(LinearSegment(p0, p1)) => vec![p0, p1],
+                        Segment::Quadratic(QuadraticSegment(p0, p1, p2)) => vec![p0, p1, p2],
+                        Segment::Cubic(CubicSegment(p0, p1, p2, p3)) => vec![p0, p1, p2, p3],
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn path(d: &str) -> Vec<Vec<Vec<Vector2>>> {
+        contours(&Shape::from_svg_path(d).unwrap())
+    }
+
+    fn assert_close(a: Vector2, b: Vector2) {
+        assert!((a - b).length() < 1e-9, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn repeats_implicit_commands() {
+        let square = vec![vec![
+            vec![v(0.0, 0.0), v(2.0, 0.0)],
+            vec![v(2.0, 0.0), v(2.0, 2.0)],
+            vec![v(2.0, 2.0), v(0.0, 2.0)],
+            vec![v(0.0, 2.0), v(0.0, 0.0)],
+        ]];
+        assert_eq!(path("M0 0 2 0 2 2 0 2z"), square);
+        assert_eq!(path("m0,0 2,0 0,2 -2,0z"), square);
+        assert_eq!(path("M0 0 L2 0 2 2 0 2 Z"), square);
+        assert_eq!(path("M0 0h2v2h-2z"), square);
+        assert_eq!(
+            path("M0 0 Q1 1 2 0 3 -1 4 0")[0][..2],
+            [
+                vec![v(0.0, 0.0), v(1.0, 1.0), v(2.0, 0.0)],
+                vec![v(2.0, 0.0), v(3.0, -1.0), v(4.0, 0.0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn continues_relative_commands_after_close() {
+        let contours = path("m10 10 h5 v5 z m1 1 h2 v2 z l1 -1 v2 z");
+        assert_eq!(contours.len(), 3);
+        assert_eq!(contours[1][0], vec![v(11.0, 11.0), v(13.0, 11.0)]);
+        // Without a move, the next subpath starts where the previous one was closed.
+        assert_eq!(contours[2][0], vec![v(11.0, 11.0), v(12.0, 10.0)]);
+        assert_eq!(contours[2][1], vec![v(12.0, 10.0), v(12.0, 12.0)]);
+    }
+
+    #[test]
+    fn reflects_smooth_control_points() {
+        let contours = path("M0 0 C0 1 2 1 2 0 S4 -1 4 0 s2 1 2 0");
+        assert_eq!(contours[0][1][1], v(2.0, -1.0));
+        assert_eq!(contours[0][2][1], v(4.0, 1.0));
+        assert_eq!(contours[0][2][3], v(6.0, 0.0));
+        let contours = path("M0 0 Q1 1 2 0 T4 0 t2 0");
+        assert_eq!(contours[0][1], vec![v(2.0, 0.0), v(3.0, -1.0), v(4.0, 0.0)]);
+        assert_eq!(contours[0][2], vec![v(4.0, 0.0), v(5.0, 1.0), v(6.0, 0.0)]);
+        // After any other command, the first control point is the current point. A quadratic
+        // control point that coincides with an endpoint is moved to the middle of the edge.
+        let contours = path("M0 0 L1 0 S2 1 3 0 L4 0 T5 0");
+        assert_eq!(contours[0][1][1], v(1.0, 0.0));
+        assert_eq!(contours[0][3][1], v(4.5, 0.0));
+        let contours = path("M0 0 Q1 1 2 0 S3 1 4 0");
+        assert_eq!(contours[0][1][1], v(2.0, 0.0));
+    }
+
+    #[test]
+    fn parses_compact_arc_flags() {
+        let expected = path("M0 0 a1 1 0 0 0 1 1");
+        assert_eq!(path("M0 0 a1 1 0 00 1 1"), expected);
+        assert_eq!(path("M0 0a1,1,0,0,0,1,1"), expected);
+        assert_eq!(path("M0 0a1 1 0 001 1"), expected);
+        let arc = expected[0].iter().find(|edge| edge.len() == 4).unwrap();
+        assert_eq!(arc[3], v(1.0, 1.0));
+        assert!(Shape::from_svg_path("M0 0 a1 1 0 20 1 1").is_err());
+    }
+
+    #[test]
+    fn approximates_arcs() {
+        // A semicircle, split into two quarters, whose radius is scaled up to fit.
+        for d in ["M0 0 A5 5 0 0 1 10 0", "M0 0 A1 1 0 0 1 10 0"] {
+            let contours = path(d);
+            let arcs = &contours[0][..2];
+            assert!(arcs.iter().all(|edge| edge.len() == 4));
+            assert_eq!(arcs[0][0], v(0.0, 0.0));
+            assert_close(arcs[0][3], v(5.0, -5.0));
+            assert_eq!(arcs[1][3], v(10.0, 0.0));
+            for arc in arcs {
+                let (p0, p1, p2, p3) = (arc[0], arc[1], arc[2], arc[3]);
+                let mid = (p0 + p3) * 0.125 + (p1 + p2) * 0.375;
+                assert!(((mid - v(5.0, 0.0)).length() - 5.0).abs() < 0.01);
+            }
+        }
+        // The other sweep direction and the large arc flag.
+        assert_close(path("M0 0 A5 5 0 0 0 10 0")[0][0][3], v(5.0, 5.0));
+        let contours = path("M0 0 A5 5 0 1 0 5 5");
+        assert_eq!(contours[0].iter().filter(|edge| edge.len() == 4).count(), 3);
+        assert_close(contours[0][0][3], v(-5.0, 5.0));
+        assert_close(contours[0][1][3], v(0.0, 10.0));
+        assert_eq!(contours[0][2][3], v(5.0, 5.0));
+    }
+
+    /// The extent `[l, t, r, b]` of all edge points, in SVG's y-down coordinates.
+    fn extent(svg: &SvgShape) -> [f64; 4] {
+        let points = contours(&svg.shape).into_iter().flatten().flatten();
+        points.fold(
+            [f64::MAX, f64::MAX, f64::MIN, f64::MIN],
+            |[l, t, r, b], p| [l.min(p.x), t.min(p.y), r.max(p.x), b.max(p.y)],
+        )
+    }
+
+    fn assert_extent(svg: &str, expected: [f64; 4]) {
+        let extent = extent(&load_svg_shape(svg).unwrap());
+        for (a, b) in extent.into_iter().zip(expected) {
+            assert!((a - b).abs() < 1e-9, "{extent:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn maps_view_box_to_viewport() {
+        let svg = |aspect: &str| {
+            format!(
+                r#"<svg width="200" height="100" viewBox="0 0 10 10" {aspect}>
+                    <rect width="10" height="10"/>
+                </svg>"#
+            )
+        };
+        assert_extent(&svg(""), [50.0, 0.0, 150.0, 100.0]);
+        assert_extent(
+            &svg(r#"preserveAspectRatio="xMaxYMid""#),
+            [100.0, 0.0, 200.0, 100.0],
+        );
+        assert_extent(
+            &svg(r#"preserveAspectRatio="xMinYMax slice""#),
+            [0.0, -100.0, 200.0, 100.0],
+        );
+        assert_extent(
+            &svg(r#"preserveAspectRatio="none""#),
+            [0.0, 0.0, 200.0, 100.0],
+        );
+        let view_box = load_svg_shape(&svg("")).unwrap().view_box.unwrap();
+        assert_eq!(
+            [view_box.l, view_box.b, view_box.r, view_box.t],
+            [0.0, 0.0, 200.0, 100.0]
+        );
+        let view_box = load_svg_shape(r#"<svg viewBox="-5 -5 10 20"/>"#)
+            .unwrap()
+            .view_box
+            .unwrap();
+        assert_eq!(
+            [view_box.l, view_box.b, view_box.r, view_box.t],
+            [-5.0, -5.0, 5.0, 15.0]
+        );
+        assert!(load_svg_shape(&svg(r#"preserveAspectRatio="xMidYMin cover""#)).is_err());
+    }
+
+    #[test]
+    fn applies_nested_transforms() {
+        assert_extent(
+            r#"<svg width="100" height="100" viewBox="0 0 100 100">
+                <g transform="translate(10 20)">
+                    <svg x="5" y="5" width="20" height="20" viewBox="0 0 10 10">
+                        <g transform="scale(2)"><rect width="1" height="1"/></g>
+                    </svg>
+                </g>
+                <g transform="translate(50, 0)"><rect width="1" height="1"/></g>
+            </svg>"#,
+            [15.0, 0.0, 51.0, 29.0],
+        );
+        assert_extent(
+            r#"<svg><g transform="translate(10) scale(2, 3)"><rect width="1" height="1"/></g></svg>"#,
+            [10.0, 0.0, 12.0, 3.0],
+        );
+    }
+
+    #[test]
+    fn rotates_around_center() {
+        // Rotating by 90 degrees around (5, 5) maps (x, y) to (10 - y, x).
+        assert_extent(
+            r#"<svg><rect width="2" height="1" transform="rotate(90 5 5)"/></svg>"#,
+            [9.0, 0.0, 10.0, 2.0],
+        );
+        assert_extent(
+            r#"<svg><rect width="2" height="1" transform="rotate(90)"/></svg>"#,
+            [-1.0, 0.0, 0.0, 2.0],
+        );
+        assert!(load_svg_shape(r#"<svg><rect transform="rotate(90 5)"/></svg>"#).is_err());
+    }
+
+    #[test]
+    fn prefers_style_fill_rule() {
+        let fill_rule = |svg: &str| load_svg_shape(svg).unwrap().fill_rule;
+        assert_eq!(
+            fill_rule(
+                r#"<svg style="fill: red; fill-rule: evenodd" fill-rule="nonzero">
+                    <path d="M0 0h1v1z"/>
+                </svg>"#
+            ),
+            FillRule::Odd
+        );
+        assert_eq!(
+            fill_rule(
+                r#"<svg fill-rule="evenodd">
+                    <g style="fill-rule:nonzero"><path d="M0 0h1v1z"/></g>
+                </svg>"#
+            ),
+            FillRule::NonZero
+        );
+        assert_eq!(
+            fill_rule(
+                r#"<svg fill-rule="evenodd">
+                    <path fill-rule="inherit" d="M0 0h1v1z"/>
+                    <path fill-rule="nonzero" d="M0 0h1v1z"/>
+                </svg>"#
+            ),
+            FillRule::Odd
+        );
+    }
+
+    #[test]
+    fn rejects_mismatched_end_tags() {
+        let svg = "<svg><g><path d='M0 0h1v1z'/></svg></g>";
+        let offset = svg.find("</svg>").unwrap();
+        assert_eq!(
+            load_svg_shape(svg).err(),
+            Some(SvgError::InvalidXml { offset })
+        );
+        assert!(matches!(
+            load_svg_shape("<svg><g>").err(),
+            Some(SvgError::InvalidXml { .. })
+        ));
+    }
+}
@@ -1,5 +1,9 @@
 mod arithmetics;
+mod bitmap;
+mod bitmap_interpolation;
 mod contour;
+mod contour_combiner;
+mod distance_mapping;
 mod edge_color;
 mod edge_coloring;
 mod edge_holder;
@@ -8,15 +12,26 @@ mod edge_segment_cubic;
 mod edge_segment_linear;
 mod edge_segment_quadratic;
 mod edge_selector;
+mod edge_selector_multi_distance;
 mod edge_selector_pseudo_distance;
 mod edge_selector_true_distance;
 mod equation_solver;
+mod font;
+mod font_cff;
+mod font_metrics;
+mod font_truetype;
+mod font_variations;
+mod import_svg;
 mod msdf_edge_artifact_patcher;
 mod msdf_error_correction;
 mod msdfgen;
 mod pixel_conversion;
+mod projection;
+mod range;
 mod rasterization;
 mod scanline;
+mod sdf_error_estimation;
+mod sdf_transformation;
 mod shape;
 mod shape_description;
 mod shape_distance_finder;
@@ -24,7 +39,11 @@ mod signed_distance;
 mod vector2;
 
 pub use arithmetics::*;
+pub use bitmap::*;
+pub use bitmap_interpolation::*;
 pub use contour::*;
+pub use contour_combiner::*;
+pub use distance_mapping::*;
 pub use edge_color::*;
 pub use edge_coloring::*;
 pub use edge_holder::*;
@@ -33,15 +52,24 @@ pub use edge_segment_cubic::*;
 pub use edge_segment_linear::*;
 pub use edge_segment_quadratic::*;
 pub use edge_selector::*;
+pub use edge_selector_multi_distance::*;
 pub use edge_selector_pseudo_distance::*;
 pub use edge_selector_true_distance::*;
 pub use equation_solver::*;
+pub use font::*;
+pub use font_metrics::*;
+pub use font_variations::*;
+pub use import_svg::*;
 pub use msdf_edge_artifact_patcher::*;
 pub use msdf_error_correction::*;
 pub use msdfgen::*;
 pub use pixel_conversion::*;
+pub use projection::*;
+pub use range::*;
 pub use rasterization::*;
 pub use scanline::*;
+pub use sdf_error_estimation::*;
+pub use sdf_transformation::*;
 pub use shape::*;
 pub use shape_description::*;
 pub use shape_distance_finder::*;
@@ -0,0 +1,206 @@
+use crate::{
+    assert_msdf_channels, interpolate, median, mix, solve_quadratic, BitmapMut, BitmapRef,
+    ContourCombiner, GeneratorConfig, OverlappingContourCombiner, PseudoDistanceSelector,
+    SDFTransformation, Shape, ShapeDistanceFinder, SimpleContourCombiner, Vector2,
+};
+
+#[inline]
+fn is_hotspot(am: f32, bm: f32, xm: f32) -> bool {
+    (am > 0.5 && bm > 0.5 && xm < 0.5) || (am < 0.5 && bm < 0.5 && xm > 0.5)
+}
+
+#[inline]
+fn mix_f32(a: f32, b: f32, t: f64) -> f32 {
+    mix(a as f64, b as f64, t) as f32
+}
+
+fn find_linear_channel_hotspots<const N: usize>(
+    t: &mut Vec<f64>,
+    a: &[f32; N],
+    b: &[f32; N],
+    d_a: f32,
+    d_b: f32,
+) {
+    let x = d_a as f64 / (d_a - d_b) as f64;
+    if x > 0.0 && x < 1.0 {
+        let am = median(a[0], a[1], a[2]);
+        let bm = median(b[0], b[1], b[2]);
+        let xm = median(
+            mix_f32(a[0], b[0], x),
+            mix_f32(a[1], b[1], x),
+            mix_f32(a[2], b[2], x),
+        );
+        if is_hotspot(am, bm, xm) {
+            t.push(x);
+        }
+    }
+}
+
+fn find_diagonal_channel_hotspots<const N: usize>(
+    t: &mut Vec<f64>,
+    [a, b, c, d]: [&[f32; N]; 4],
+    d_a: f32,
+    d_b: f32,
+    d_c: f32,
+    d_d: f32,
+) {
+    let mut x = [0.0; 2];
+    let solutions = solve_quadratic(
+        &mut x,
+        ((d_d - d_c) - (d_b - d_a)) as f64,
+        (d_c + d_b - d_a - d_a) as f64,
+        d_a as f64,
+    )
+    .unwrap_or(0);
+    for &x in &x[..solutions] {
+        if x > 0.0 && x < 1.0 {
+            let am = median(a[0], a[1], a[2]);
+            let bm = median(b[0], b[1], b[2]);
+            let xm = median(
+                mix_f32(mix_f32(a[0], b[0], x), mix_f32(c[0], d[0], x), x),
+                mix_f32(mix_f32(a[1], b[1], x), mix_f32(c[1], d[1], x), x),
+                mix_f32(mix_f32(a[2], b[2], x), mix_f32(c[2], d[2], x), x),
+            );
+            if is_hotspot(am, bm, xm) {
+                t.push(x);
+            }
+        }
+    }
+}
+
+fn find_linear_hotspots<const N: usize>(t: &mut Vec<f64>, a: &[f32; N], b: &[f32; N]) {
+    t.clear();
+    find_linear_channel_hotspots(t, a, b, a[1] - a[0], b[1] - b[0]);
+    find_linear_channel_hotspots(t, a, b, a[2] - a[1], b[2] - b[1]);
+    find_linear_channel_hotspots(t, a, b, a[0] - a[2], b[0] - b[2]);
+}
+
+fn find_diagonal_hotspots<const N: usize>(t: &mut Vec<f64>, texels: [&[f32; N]; 4]) {
+    let [a, b, c, d] = texels;
+    t.clear();
+    for (i, j) in [(1, 0), (2, 1), (0, 2)] {
+        find_diagonal_channel_hotspots(
+            t,
+            texels,
+            a[i] - a[j],
+            b[i] - b[j],
+            c[i] - c[j],
+            d[i] - d[j],
+        );
+    }
+}
+
+/// Finds the points between adjacent texels where the interpolated median crosses the edge
+/// even though the texels on both sides are on the same side of it.
+fn find_hotspots<const N: usize>(sdf: BitmapRef<'_, f32, N>) -> Vec<Vector2> {
+    let (width, height) = (sdf.width(), sdf.height());
+    let mut hotspots = Vec::new();
+    let mut t = Vec::with_capacity(6);
+    // All hotspots intersect either the horizontal, vertical, or diagonal line that connects
+    // neighboring texels.
+    for y in 0..height {
+        for x in 0..width.saturating_sub(1) {
+            find_linear_hotspots(&mut t, sdf.pixel(x, y), sdf.pixel(x + 1, y));
+            for &t in &t {
+                hotspots.push(Vector2::new(x as f64 + 0.5 + t, y as f64 + 0.5));
+            }
+        }
+    }
+    for y in 0..height.saturating_sub(1) {
+        for x in 0..width {
+            find_linear_hotspots(&mut t, sdf.pixel(x, y), sdf.pixel(x, y + 1));
+            for &t in &t {
+                hotspots.push(Vector2::new(x as f64 + 0.5, y as f64 + 0.5 + t));
+            }
+        }
+    }
+    for y in 0..height.saturating_sub(1) {
+        for x in 0..width.saturating_sub(1) {
+            let lb = sdf.pixel(x, y);
+            let rb = sdf.pixel(x + 1, y);
+            let lt = sdf.pixel(x, y + 1);
+            let rt = sdf.pixel(x + 1, y + 1);
+            find_diagonal_hotspots(&mut t, [lb, rb, lt, rt]);
+            for &t in &t {
+                hotspots.push(Vector2::new(x as f64 + 0.5 + t, y as f64 + 0.5 + t));
+            }
+            find_diagonal_hotspots(&mut t, [lt, rt, lb, rb]);
+            for &t in &t {
+                hotspots.push(Vector2::new(x as f64 + 0.5 + t, y as f64 + 1.5 - t));
+            }
+        }
+    }
+    hotspots
+}
+
+fn patch_edge_artifacts<C, const N: usize>(
+    mut sdf: BitmapMut<'_, f32, N>,
+    shape: &Shape,
+    transformation: &SDFTransformation,
+) where
+    C: ContourCombiner<Selector = PseudoDistanceSelector>,
+{
+    let mut distance_finder = ShapeDistanceFinder::<C>::new(shape);
+    let hotspots = find_hotspots(sdf.as_ref());
+    let mut artifacts = Vec::with_capacity(hotspots.len());
+    for hotspot in hotspots {
+        let mut coord = hotspot;
+        if shape.inverse_y_axis {
+            coord.y = sdf.height() as f64 - coord.y;
+        }
+        let pos = transformation.projection.unproject(coord);
+        let sd = transformation
+            .distance_mapping
+            .map(distance_finder.distance(pos)) as f32;
+
+        // Store hotspot's closest texel's current color
+        let (x, y) = (hotspot.x as usize, hotspot.y as usize);
+        let texel = *sdf.pixel(x, y);
+        // Sample previous color at hotspot position
+        let msd = interpolate(sdf.as_ref(), hotspot);
+        let old_ssd = median(msd[0], msd[1], msd[2]);
+        // Flatten hotspot's closest texel
+        let med = median(texel[0], texel[1], texel[2]);
+        sdf.pixel_mut(x, y)[..3].fill(med);
+        // Sample corrected color at hotspot position
+        let msd = interpolate(sdf.as_ref(), hotspot);
+        let new_ssd = median(msd[0], msd[1], msd[2]);
+        // Revert modified texel
+        *sdf.pixel_mut(x, y) = texel;
+
+        // Consider hotspot an artifact if flattening improved the sample
+        if (new_ssd - sd).abs() < (old_ssd - sd).abs() {
+            artifacts.push((x, y));
+        }
+    }
+    for (x, y) in artifacts {
+        let pixel = sdf.pixel_mut(x, y);
+        let med = median(pixel[0], pixel[1], pixel[2]);
+        pixel[..3].fill(med);
+    }
+}
+
+/// The legacy edge artifact patcher. Finds points where the interpolated MSDF crosses the edge
+/// unexpectedly, and flattens the nearest texel if that brings the sample closer to the exact
+/// pseudo-distance. Panics unless the bitmap has 3 or 4 channels.
+pub fn msdf_patch_edge_artifacts<const N: usize>(
+    sdf: BitmapMut<'_, f32, N>,
+    shape: &Shape,
+    transformation: &SDFTransformation,
+    config: &GeneratorConfig,
+) {
+    assert_msdf_channels::<N>();
+    if config.overlap_support {
+        patch_edge_artifacts::<OverlappingContourCombiner<PseudoDistanceSelector>, N>(
+            sdf,
+            shape,
+            transformation,
+        );
+    } else {
+        patch_edge_artifacts::<SimpleContourCombiner<PseudoDistanceSelector>, N>(
+            sdf,
+            shape,
+            transformation,
+        );
+    }
+}
@@ -0,0 +1,1111 @@
+use crate::{
+    fabs, interpolate, median, mix, solve_quadratic, Bitmap, BitmapMut, BitmapRef, ContourCombiner,
+    DistanceMapping, EdgeColor, GeneratorConfig, OverlappingContourCombiner,
+    PseudoDistanceSelector, SDFTransformation, Shape, ShapeDistanceFinder, SimpleContourCombiner,
+    Vector2,
+};
+
+const ARTIFACT_T_EPSILON: f64 = 0.01;
+const PROTECTION_RADIUS_TOLERANCE: f64 = 1.001;
+
+const CLASSIFIER_FLAG_CANDIDATE: u8 = 0x01;
+const CLASSIFIER_FLAG_ARTIFACT: u8 = 0x02;
+
+const STENCIL_ERROR: u8 = 1;
+const STENCIL_PROTECTED: u8 = 2;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ErrorCorrectionMode {
+    /// Skips error correction pass.
+    Disabled,
+    /// Corrects all discontinuities of the distance field regardless if edges are adversely
+    /// affected.
+    Indiscriminate,
+    /// Corrects artifacts at edges and other discontinuous distances only if it does not affect
+    /// edges or corners.
+    EdgePriority,
+    /// Only corrects artifacts at edges.
+    EdgeOnly,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DistanceCheckMode {
+    /// Never computes exact shape distance.
+    DoNotCheckDistance,
+    /// Only computes exact shape distance at edges. Provides a good balance between speed and
+    /// precision.
+    CheckDistanceAtEdge,
+    /// Computes and compares the exact shape distance for each suspected artifact.
+    AlwaysCheckDistance,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct ErrorCorrectionConfig {
+    pub mode: ErrorCorrectionMode,
+    pub distance_check_mode: DistanceCheckMode,
+    /// The minimum ratio between the actual and maximum expected distance delta to be considered
+    /// an error.
+    pub min_deviation_ratio: f64,
+    /// The minimum ratio between the pre-correction distance error and the post-correction
+    /// distance error. Has no effect for `DistanceCheckMode::DoNotCheckDistance`.
+    pub min_improve_ratio: f64,
+}
+
+impl ErrorCorrectionConfig {
+    pub const DEFAULT_MIN_DEVIATION_RATIO: f64 = 10.0 / 9.0;
+    pub const DEFAULT_MIN_IMPROVE_RATIO: f64 = 10.0 / 9.0;
+}
+
+impl Default for ErrorCorrectionConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            mode: ErrorCorrectionMode::EdgePriority,
+            distance_check_mode: DistanceCheckMode::CheckDistanceAtEdge,
+            min_deviation_ratio: Self::DEFAULT_MIN_DEVIATION_RATIO,
+            min_improve_ratio: Self::DEFAULT_MIN_IMPROVE_RATIO,
+        }
+    }
+}
+
+#[inline]
+fn median3<const N: usize>(msd: &[f32; N]) -> f32 {
+    median(msd[0], msd[1], msd[2])
+}
+
+/// Panics unless the bitmap has 3 or 4 channels, which is what multi-channel correction expects.
+#[inline]
+pub(crate) fn assert_msdf_channels<const N: usize>() {
+    assert!(N == 3 || N == 4, "the MSDF must have 3 or 4 channels");
+}
+
+trait ArtifactClassifier {
+    /// Evaluates if the median value `xm` interpolated at `xt` in the range between `am` at `at`
+    /// and `bm` at `bt` indicates an artifact.
+    fn range_test(&self, at: f64, bt: f64, xt: f64, am: f32, bm: f32, xm: f32) -> u8;
+    /// Returns true if the combined results of the tests performed on the median value `m`
+    /// interpolated at `t` indicate an artifact.
+    fn evaluate(&mut self, t: f64, m: f32, flags: u8) -> bool;
+}
+
+/// Recognizes artifacts based on the contents of the SDF alone.
+struct BaseArtifactClassifier {
+    span: f64,
+    protected: bool,
+}
+
+impl BaseArtifactClassifier {
+    #[inline]
+    fn new(span: f64, protected: bool) -> Self {
+        Self { span, protected }
+    }
+}
+
+impl ArtifactClassifier for BaseArtifactClassifier {
+    fn range_test(&self, at: f64, bt: f64, xt: f64, am: f32, bm: f32, xm: f32) -> u8 {
+        // For protected texels, only consider inversion artifacts (interpolated median has
+        // different sign than boundaries). For the rest, it is sufficient that the interpolated
+        // median is outside its boundaries.
+        if (am > 0.5 && bm > 0.5 && xm <= 0.5)
+            || (am < 0.5 && bm < 0.5 && xm >= 0.5)
+            || (!self.protected && median(am, bm, xm) != xm)
+        {
+            let ax_span = (xt - at) * self.span;
+            let bx_span = (bt - xt) * self.span;
+            let (am, bm, xm) = (am as f64, bm as f64, xm as f64);
+            // Check if the interpolated median's value is in the expected range based on its
+            // distance (span) from boundaries a, b.
+            if !(xm >= am - ax_span
+                && xm <= am + ax_span
+                && xm >= bm - bx_span
+                && xm <= bm + bx_span)
+            {
+                return CLASSIFIER_FLAG_CANDIDATE | CLASSIFIER_FLAG_ARTIFACT;
+            }
+            return CLASSIFIER_FLAG_CANDIDATE;
+        }
+        0
+    }
+
+    #[inline]
+    fn evaluate(&mut self, _t: f64, _m: f32, flags: u8) -> bool {
+        (flags & CLASSIFIER_FLAG_ARTIFACT) != 0
+    }
+}
+
+/// Evaluates the exact shape distance to find additional artifacts at a significant performance
+/// cost.
+struct ShapeDistanceChecker<'a, C: ContourCombiner, const N: usize> {
+    shape_coord: Vector2,
+    sdf_coord: Vector2,
+    msd: [f32; N],
+    protected: bool,
+    distance_finder: ShapeDistanceFinder<C>,
+    sdf: BitmapRef<'a, f32, N>,
+    distance_mapping: DistanceMapping,
+    texel_size: Vector2,
+    min_improve_ratio: f64,
+}
+
+impl<'a, C, const N: usize> ShapeDistanceChecker<'a, C, N>
+where
+    C: ContourCombiner<Selector = PseudoDistanceSelector>,
+{
+    fn new(
+        sdf: BitmapRef<'a, f32, N>,
+        shape: &Shape,
+        transformation: &SDFTransformation,
+        min_improve_ratio: f64,
+    ) -> Self {
+        let mut texel_size = transformation
+            .projection
+            .unproject_vector(Vector2::from(1.0));
+        if shape.inverse_y_axis {
+            texel_size.y = -texel_size.y;
+        }
+        Self {
+            shape_coord: Vector2::default(),
+            sdf_coord: Vector2::default(),
+            msd: [0.0; N],
+            protected: false,
+            distance_finder: ShapeDistanceFinder::new(shape),
+            sdf,
+            distance_mapping: transformation.distance_mapping,
+            texel_size,
+            min_improve_ratio,
+        }
+    }
+
+    #[inline]
+    fn classifier(
+        &mut self,
+        direction: Vector2,
+        span: f64,
+    ) -> ShapeDistanceClassifier<'_, 'a, C, N> {
+        ShapeDistanceClassifier {
+            base: BaseArtifactClassifier::new(span, self.protected),
+            direction,
+            parent: self,
+        }
+    }
+}
+
+struct ShapeDistanceClassifier<'c, 'a, C: ContourCombiner, const N: usize> {
+    base: BaseArtifactClassifier,
+    direction: Vector2,
+    parent: &'c mut ShapeDistanceChecker<'a, C, N>,
+}
+
+impl<'c, 'a, C, const N: usize> ArtifactClassifier for ShapeDistanceClassifier<'c, 'a, C, N>
+where
+    C: ContourCombiner<Selector = PseudoDistanceSelector>,
+{
+    #[inline]
+    fn range_test(&self, at: f64, bt: f64, xt: f64, am: f32, bm: f32, xm: f32) -> u8 {
+        self.base.range_test(at, bt, xt, am, bm, xm)
+    }
+
+    fn evaluate(&mut self, t: f64, _m: f32, flags: u8) -> bool {
+        if flags & CLASSIFIER_FLAG_CANDIDATE == 0 {
+            return false;
+        }
+        // Skip expensive distance evaluation if the point has already been classified as an
+        // artifact by the base classifier.
+        if flags & CLASSIFIER_FLAG_ARTIFACT != 0 {
+            return true;
+        }
+        let parent = &mut *self.parent;
+        let t_vector = self.direction * t;
+        // Compute the color that would be currently interpolated at the artifact candidate's
+        // position.
+        let old_msd = interpolate(parent.sdf, parent.sdf_coord + t_vector);
+        // Compute the color that would be interpolated at the artifact candidate's position if
+        // error correction was applied on the current texel.
+        let a_weight = (1.0 - fabs(t_vector.x)) * (1.0 - fabs(t_vector.y));
+        let a_psd = median3(&parent.msd);
+        let mut new_msd = [0.0f32; 3];
+        for i in 0..3 {
+            new_msd[i] = (old_msd[i] as f64 + a_weight * (a_psd - parent.msd[i]) as f64) as f32;
+        }
+        // Compute the evaluated distance (interpolated median) before and after error
+        // correction, as well as the exact shape distance.
+        let old_psd = median3(&old_msd);
+        let new_psd = median3(&new_msd);
+        let ref_psd = parent.distance_mapping.map(
+            parent
+                .distance_finder
+                .distance(parent.shape_coord + t_vector * parent.texel_size),
+        ) as f32;
+        // Compare the differences of the exact distance and the before and after distances.
+        parent.min_improve_ratio * ((new_psd - ref_psd).abs() as f64)
+            < (old_psd - ref_psd).abs() as f64
+    }
+}
+
+/// Checks if there is an edge between two texels a, b in the channel specified by index.
+fn edge_between_texels_channel<const N: usize>(a: &[f32; N], b: &[f32; N], channel: usize) -> bool {
+    // Find interpolation ratio t (0 < t < 1) where an edge is expected
+    // (mix(a[channel], b[channel], t) == 0.5).
+    let t = (a[channel] as f64 - 0.5) / (a[channel] - b[channel]) as f64;
+    if t > 0.0 && t < 1.0 {
+        // Interpolate channel values at t.
+        let c = [
+            mix(a[0] as f64, b[0] as f64, t) as f32,
+            mix(a[1] as f64, b[1] as f64, t) as f32,
+            mix(a[2] as f64, b[2] as f64, t) as f32,
+        ];
+        // This is only an edge if the zero-distance channel is the median.
+        return median3(&c) == c[channel];
+    }
+    false
+}
+
+/// Returns a bit mask of which channels contribute to an edge between the two texels a, b.
+fn edge_between_texels<const N: usize>(a: &[f32; N], b: &[f32; N]) -> u8 {
+    let mut mask = 0;
+    if edge_between_texels_channel(a, b, 0) {
+        mask |= EdgeColor::Red.bits();
+    }
+    if edge_between_texels_channel(a, b, 1) {
+        mask |= EdgeColor::Green.bits();
+    }
+    if edge_between_texels_channel(a, b, 2) {
+        mask |= EdgeColor::Blue.bits();
+    }
+    mask
+}
+
+/// Marks texel as protected if one of its non-median channels is present in the channel mask.
+fn protect_extreme_channels<const N: usize>(stencil: &mut u8, msd: &[f32; N], m: f32, mask: u8) {
+    if (mask & EdgeColor::Red.bits() != 0 && msd[0] != m)
+        || (mask & EdgeColor::Green.bits() != 0 && msd[1] != m)
+        || (mask & EdgeColor::Blue.bits() != 0 && msd[2] != m)
+    {
+        *stencil |= STENCIL_PROTECTED;
+    }
+}
+
+/// Returns the median of the linear interpolation of texels a, b at t.
+fn interpolated_median<const N: usize>(a: &[f32; N], b: &[f32; N], t: f64) -> f32 {
+    median(
+        mix(a[0] as f64, b[0] as f64, t),
+        mix(a[1] as f64, b[1] as f64, t),
+        mix(a[2] as f64, b[2] as f64, t),
+    ) as f32
+}
+
+/// Returns the median of the bilinear interpolation with the given constant, linear, and
+/// quadratic terms at t.
+fn interpolated_median_quadratic<const N: usize>(
+    a: &[f32; N],
+    l: &[f32; 3],
+    q: &[f32; 3],
+    t: f64,
+) -> f32 {
+    median(
+        t * (t * q[0] as f64 + l[0] as f64) + a[0] as f64,
+        t * (t * q[1] as f64 + l[1] as f64) + a[1] as f64,
+        t * (t * q[2] as f64 + l[2] as f64) + a[2] as f64,
+    ) as f32
+}
+
+/// Checks if a linear interpolation artifact will occur at a point where two specific color
+/// channels are equal - such points have extreme median values.
+fn has_linear_artifact_inner<const N: usize>(
+    classifier: &mut impl ArtifactClassifier,
+    am: f32,
+    bm: f32,
+    a: &[f32; N],
+    b: &[f32; N],
+    d_a: f32,
+    d_b: f32,
+) -> bool {
+    // Find interpolation ratio t (0 < t < 1) where two color channels are equal
+    // (mix(d_a, d_b, t) == 0).
+    let t = d_a as f64 / (d_a - d_b) as f64;
+    if t > ARTIFACT_T_EPSILON && t < 1.0 - ARTIFACT_T_EPSILON {
+        // Interpolate median at t and let the classifier decide if its value indicates an
+        // artifact.
+        let xm = interpolated_median(a, b, t);
+        let flags = classifier.range_test(0.0, 1.0, t, am, bm, xm);
+        return classifier.evaluate(t, xm, flags);
+    }
+    false
+}
+
+/// Checks if a bilinear interpolation artifact will occur at a point where two specific color
+/// channels are equal - such points have extreme median values.
+#[allow(clippy::too_many_arguments)]
+fn has_diagonal_artifact_inner<const N: usize>(
+    classifier: &mut impl ArtifactClassifier,
+    am: f32,
+    dm: f32,
+    a: &[f32; N],
+    l: &[f32; 3],
+    q: &[f32; 3],
+    d_a: f32,
+    d_bc: f32,
+    d_d: f32,
+    t_ex0: f64,
+    t_ex1: f64,
+) -> bool {
+    // Find interpolation ratios t (0 < t[i] < 1) where two color channels are equal.
+    let mut t = [0.0; 2];
+    let solutions = solve_quadratic(
+        &mut t,
+        (d_d - d_bc + d_a) as f64,
+        (d_bc - d_a - d_a) as f64,
+        d_a as f64,
+    )
+    .unwrap_or(0);
+    for &t in &t[..solutions] {
+        // Solutions t == 0 and t == 1 are singularities and occur very often because two
+        // channels are usually equal at texels.
+        if t > ARTIFACT_T_EPSILON && t < 1.0 - ARTIFACT_T_EPSILON {
+            // Interpolate median xm at t.
+            let xm = interpolated_median_quadratic(a, l, q, t);
+            // Determine if xm deviates too much from medians of a, d.
+            let mut range_flags = classifier.range_test(0.0, 1.0, t, am, dm, xm);
+            // Additionally, check xm against the interpolated medians at the local extremes
+            // t_ex0, t_ex1.
+            for t_ex in [t_ex0, t_ex1] {
+                if t_ex > 0.0 && t_ex < 1.0 {
+                    let mut t_end = [0.0, 1.0];
+                    let mut em = [am, dm];
+                    let i = (t_ex > t) as usize;
+                    t_end[i] = t_ex;
+                    em[i] = interpolated_median_quadratic(a, l, q, t_ex);
+                    range_flags |= classifier.range_test(t_end[0], t_end[1], t, em[0], em[1], xm);
+                }
+            }
+            if classifier.evaluate(t, xm, range_flags) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Checks if a linear interpolation artifact will occur inbetween two horizontally or vertically
+/// adjacent texels a, b.
+fn has_linear_artifact<const N: usize>(
+    mut classifier: impl ArtifactClassifier,
+    am: f32,
+    a: &[f32; N],
+    b: &[f32; N],
+) -> bool {
+    let bm = median3(b);
+    // Out of the pair, only report artifacts for the texel further from the edge to minimize
+    // side effects.
+    (am - 0.5).abs() >= (bm - 0.5).abs()
+        && (
+            // Check points where each pair of color channels meets.
+            has_linear_artifact_inner(&mut classifier, am, bm, a, b, a[1] - a[0], b[1] - b[0])
+                || has_linear_artifact_inner(
+                    &mut classifier,
+                    am,
+                    bm,
+                    a,
+                    b,
+                    a[2] - a[1],
+                    b[2] - b[1],
+                )
+                || has_linear_artifact_inner(
+                    &mut classifier,
+                    am,
+                    bm,
+                    a,
+                    b,
+                    a[0] - a[2],
+                    b[0] - b[2],
+                )
+        )
+}
+
+/// Checks if a bilinear interpolation artifact will occur inbetween two diagonally adjacent
+/// texels a, d (with b, c forming the other diagonal).
+fn has_diagonal_artifact<const N: usize>(
+    mut classifier: impl ArtifactClassifier,
+    am: f32,
+    a: &[f32; N],
+    b: &[f32; N],
+    c: &[f32; N],
+    d: &[f32; N],
+) -> bool {
+    let dm = median3(d);
+    // Out of the pair, only report artifacts for the texel further from the edge to minimize
+    // side effects.
+    if (am - 0.5).abs() < (dm - 0.5).abs() {
+        return false;
+    }
+    let abc = [a[0] - b[0] - c[0], a[1] - b[1] - c[1], a[2] - b[2] - c[2]];
+    // Compute the linear terms for bilinear interpolation.
+    let l = [-a[0] - abc[0], -a[1] - abc[1], -a[2] - abc[2]];
+    // Compute the quadratic terms for bilinear interpolation.
+    let q = [d[0] + abc[0], d[1] + abc[1], d[2] + abc[2]];
+    // Compute interpolation ratios t_ex (0 < t_ex[i] < 1) for the local extremes of each color
+    // channel (the derivative 2*q[i]*t_ex[i]+l[i] == 0).
+    let t_ex = [
+        -0.5 * l[0] as f64 / q[0] as f64,
+        -0.5 * l[1] as f64 / q[1] as f64,
+        -0.5 * l[2] as f64 / q[2] as f64,
+    ];
+    // Check points where each pair of color channels meets.
+    has_diagonal_artifact_inner(
+        &mut classifier,
+        am,
+        dm,
+        a,
+        &l,
+        &q,
+        a[1] - a[0],
+        b[1] - b[0] + c[1] - c[0],
+        d[1] - d[0],
+        t_ex[0],
+        t_ex[1],
+    ) || has_diagonal_artifact_inner(
+        &mut classifier,
+        am,
+        dm,
+        a,
+        &l,
+        &q,
+        a[2] - a[1],
+        b[2] - b[1] + c[2] - c[1],
+        d[2] - d[1],
+        t_ex[1],
+        t_ex[2],
+    ) || has_diagonal_artifact_inner(
+        &mut classifier,
+        am,
+        dm,
+        a,
+        &l,
+        &q,
+        a[0] - a[2],
+        b[0] - b[2] + c[0] - c[2],
+        d[0] - d[2],
+        t_ex[2],
+        t_ex[0],
+    )
+}
+
+/// Neighbors of a texel, in the order left, bottom, right, top.
+type Neighbors<'a, const N: usize> = [Option<&'a [f32; N]>; 4];
+
+#[inline]
+fn neighbors<const N: usize>(sdf: BitmapRef<'_, f32, N>, x: usize, y: usize) -> Neighbors<'_, N> {
+    [
+        (x > 0).then(|| sdf.pixel(x - 1, y)),
+        (y > 0).then(|| sdf.pixel(x, y - 1)),
+        (x + 1 < sdf.width()).then(|| sdf.pixel(x + 1, y)),
+        (y + 1 < sdf.height()).then(|| sdf.pixel(x, y + 1)),
+    ]
+}
+
+/// Performs error correction on a multi-channel distance field by marking texels which cause
+/// interpolation artifacts in a stencil, then replacing their channels with the median.
+struct MSDFErrorCorrection {
+    stencil: Bitmap<u8, 1>,
+    transformation: SDFTransformation,
+    min_deviation_ratio: f64,
+    min_improve_ratio: f64,
+}
+
+impl MSDFErrorCorrection {
+    fn new(width: usize, height: usize, transformation: SDFTransformation) -> Self {
+        Self {
+            stencil: Bitmap::new(width, height),
+            transformation,
+            min_deviation_ratio: ErrorCorrectionConfig::DEFAULT_MIN_DEVIATION_RATIO,
+            min_improve_ratio: ErrorCorrectionConfig::DEFAULT_MIN_IMPROVE_RATIO,
+        }
+    }
+
+    #[inline]
+    fn stencil_mut(&mut self, x: usize, y: usize) -> &mut u8 {
+        &mut self.stencil.pixel_mut(x, y)[0]
+    }
+
+    /// Returns the expected value delta between horizontally, vertically, and diagonally
+    /// adjacent texels, scaled by `ratio`.
+    fn spans(&self, ratio: f64) -> [f64; 3] {
+        let delta = self.transformation.distance_mapping.map_delta(1.0);
+        let projection = &self.transformation.projection;
+        [
+            Vector2::new(delta, 0.0),
+            Vector2::new(0.0, delta),
+            Vector2::from(delta),
+        ]
+        .map(|v| ratio * projection.unproject_vector(v).length())
+    }
+
+    /// Flags all texels that are interpolated at corners as protected.
+    fn protect_corners(&mut self, shape: &Shape) {
+        let (width, height) = (self.stencil.width() as i64, self.stencil.height() as i64);
+        for contour in &shape.contours {
+            let Some(last) = contour.edges.last() else {
+                continue;
+            };
+            let mut prev_color = last.color;
+            for edge in &contour.edges {
+                let common_color = (prev_color & edge.color).bits();
+                // If the color changes from prev_edge to edge, this is a corner.
+                if common_color & common_color.wrapping_sub(1) == 0 {
+                    // Find the four texels that envelop the corner and mark them as protected.
+                    let mut p = self.transformation.projection.project(edge.point(0.0));
+                    if shape.inverse_y_axis {
+                        p.y = height as f64 - p.y;
+                    }
+                    let l = (p.x - 0.5).floor() as i64;
+                    let b = (p.y - 0.5).floor() as i64;
+                    let r = l + 1;
+                    let t = b + 1;
+                    // Check that the positions are within bounds.
+                    if l < width && b < height && r >= 0 && t >= 0 {
+                        for (x, y) in [(l, b), (r, b), (l, t), (r, t)] {
+                            if x >= 0 && y >= 0 && x < width && y < height {
+                                *self.stencil_mut(x as usize, y as usize) |= STENCIL_PROTECTED;
+                            }
+                        }
+                    }
+                }
+                prev_color = edge.color;
+            }
+        }
+    }
+
+    /// Flags all texels that contribute to edges as protected.
+    fn protect_edges<const N: usize>(&mut self, sdf: BitmapRef<'_, f32, N>) {
+        let (width, height) = (sdf.width(), sdf.height());
+        let [h_radius, v_radius, d_radius] = self
+            .spans(PROTECTION_RADIUS_TOLERANCE)
+            .map(|radius| radius as f32);
+        let mut protect_pair = |(ax, ay): (usize, usize), (bx, by): (usize, usize), radius: f32| {
+            let (a, b) = (sdf.pixel(ax, ay), sdf.pixel(bx, by));
+            let (am, bm) = (median3(a), median3(b));
+            if (am - 0.5).abs() + (bm - 0.5).abs() < radius {
+                let mask = edge_between_texels(a, b);
+                protect_extreme_channels(&mut self.stencil.pixel_mut(ax, ay)[0], a, am, mask);
+                protect_extreme_channels(&mut self.stencil.pixel_mut(bx, by)[0], b, bm, mask);
+            }
+        };
+        // Horizontal texel pairs
+        for y in 0..height {
+            for x in 0..width.saturating_sub(1) {
+                protect_pair((x, y), (x + 1, y), h_radius);
+            }
+        }
+        // Vertical texel pairs
+        for y in 0..height.saturating_sub(1) {
+            for x in 0..width {
+                protect_pair((x, y), (x, y + 1), v_radius);
+            }
+        }
+        // Diagonal texel pairs
+        for y in 0..height.saturating_sub(1) {
+            for x in 0..width.saturating_sub(1) {
+                protect_pair((x, y), (x + 1, y + 1), d_radius);
+                protect_pair((x + 1, y), (x, y + 1), d_radius);
+            }
+        }
+    }
+
+    /// Flags all texels as protected.
+    fn protect_all(&mut self) {
+        for mask in self.stencil.pixels_mut() {
+            *mask |= STENCIL_PROTECTED;
+        }
+    }
+
+    /// Flags texels that are expected to cause interpolation artifacts based on analysis of the
+    /// SDF only.
+    fn find_errors<const N: usize>(&mut self, sdf: BitmapRef<'_, f32, N>) {
+        let [h_span, v_span, d_span] = self.spans(self.min_deviation_ratio);
+        let (width, height) = (sdf.width(), sdf.height());
+        for y in 0..height {
+            for x in 0..width {
+                let c = sdf.pixel(x, y);
+                let cm = median3(c);
+                let protected = (*self.stencil_mut(x, y) & STENCIL_PROTECTED) != 0;
+                let [l, b, r, t] = neighbors(sdf, x, y);
+                let classifier = |span| BaseArtifactClassifier::new(span, protected);
+                // Mark current texel c with the error flag if an artifact occurs when it's
+                // interpolated with any of its 8 neighbors.
+                let error = l.is_some_and(|l| has_linear_artifact(classifier(h_span), cm, c, l))
+                    || b.is_some_and(|b| has_linear_artifact(classifier(v_span), cm, c, b))
+                    || r.is_some_and(|r| has_linear_artifact(classifier(h_span), cm, c, r))
+                    || t.is_some_and(|t| has_linear_artifact(classifier(v_span), cm, c, t))
+                    || l.zip(b).is_some_and(|(l, b)| {
+                        let d = sdf.pixel(x - 1, y - 1);
+                        has_diagonal_artifact(classifier(d_span), cm, c, l, b, d)
+                    })
+                    || r.zip(b).is_some_and(|(r, b)| {
+                        let d = sdf.pixel(x + 1, y - 1);
+                        has_diagonal_artifact(classifier(d_span), cm, c, r, b, d)
+                    })
+                    || l.zip(t).is_some_and(|(l, t)| {
+                        let d = sdf.pixel(x - 1, y + 1);
+                        has_diagonal_artifact(classifier(d_span), cm, c, l, t, d)
+                    })
+                    || r.zip(t).is_some_and(|(r, t)| {
+                        let d = sdf.pixel(x + 1, y + 1);
+                        has_diagonal_artifact(classifier(d_span), cm, c, r, t, d)
+                    });
+                if error {
+                    *self.stencil_mut(x, y) |= STENCIL_ERROR;
+                }
+            }
+        }
+    }
+
+    /// Flags texels that are expected to cause interpolation artifacts based on analysis of the
+    /// SDF and comparison with the exact shape distance.
+    fn find_errors_with_distance_check<C, const N: usize>(
+        &mut self,
+        sdf: BitmapRef<'_, f32, N>,
+        shape: &Shape,
+    ) where
+        C: ContourCombiner<Selector = PseudoDistanceSelector>,
+    {
+        let [h_span, v_span, d_span] = self.spans(self.min_deviation_ratio);
+        let (width, height) = (sdf.width(), sdf.height());
+        let mut checker = ShapeDistanceChecker::<C, N>::new(
+            sdf,
+            shape,
+            &self.transformation,
+            self.min_improve_ratio,
+        );
+        let mut right_to_left = false;
+        for y in 0..height {
+            let row = if shape.inverse_y_axis {
+                height - y - 1
+            } else {
+                y
+            };
+            for col in 0..width {
+                let x = if right_to_left { width - col - 1 } else { col };
+                let stencil = *self.stencil_mut(x, row);
+                if stencil & STENCIL_ERROR != 0 {
+                    continue;
+                }
+                let c = sdf.pixel(x, row);
+                checker.shape_coord = self
+                    .transformation
+                    .projection
+                    .unproject(Vector2::new(x as f64 + 0.5, y as f64 + 0.5));
+                checker.sdf_coord = Vector2::new(x as f64 + 0.5, row as f64 + 0.5);
+                checker.msd = *c;
+                checker.protected = (stencil & STENCIL_PROTECTED) != 0;
+                let cm = median3(c);
+                let [l, b, r, t] = neighbors(sdf, x, row);
+                let checker = &mut checker;
+                // Mark current texel c with the error flag if an artifact occurs when it's
+                // interpolated with any of its 8 neighbors.
+                let error = l.is_some_and(|l| {
+                    has_linear_artifact(
+                        checker.classifier(Vector2::new(-1.0, 0.0), h_span),
+                        cm,
+                        c,
+                        l,
+                    )
+                }) || b.is_some_and(|b| {
+                    has_linear_artifact(
+                        checker.classifier(Vector2::new(0.0, -1.0), v_span),
+                        cm,
+                        c,
+                        b,
+                    )
+                }) || r.is_some_and(|r| {
+                    has_linear_artifact(
+                        checker.classifier(Vector2::new(1.0, 0.0), h_span),
+                        cm,
+                        c,
+                        r,
+                    )
+                }) || t.is_some_and(|t| {
+                    has_linear_artifact(
+                        checker.classifier(Vector2::new(0.0, 1.0), v_span),
+                        cm,
+                        c,
+                        t,
+                    )
+                }) || l.zip(b).is_some_and(|(l, b)| {
+                    let d = sdf.pixel(x - 1, row - 1);
+                    let classifier = checker.classifier(Vector2::new(-1.0, -1.0), d_span);
+                    has_diagonal_artifact(classifier, cm, c, l, b, d)
+                }) || r.zip(b).is_some_and(|(r, b)| {
+                    let d = sdf.pixel(x + 1, row - 1);
+                    let classifier = checker.classifier(Vector2::new(1.0, -1.0), d_span);
+                    has_diagonal_artifact(classifier, cm, c, r, b, d)
+                }) || l.zip(t).is_some_and(|(l, t)| {
+                    let d = sdf.pixel(x - 1, row + 1);
+                    let classifier = checker.classifier(Vector2::new(-1.0, 1.0), d_span);
+                    has_diagonal_artifact(classifier, cm, c, l, t, d)
+                }) || r.zip(t).is_some_and(|(r, t)| {
+                    let d = sdf.pixel(x + 1, row + 1);
+                    let classifier = checker.classifier(Vector2::new(1.0, 1.0), d_span);
+                    has_diagonal_artifact(classifier, cm, c, r, t, d)
+                });
+                if error {
+                    *self.stencil_mut(x, row) |= STENCIL_ERROR;
+                }
+            }
+            right_to_left = !right_to_left;
+        }
+    }
+
+    /// Modifies the MSDF so that all texels with the error flag are converted to single-channel.
+    fn apply<const N: usize>(&self, mut sdf: BitmapMut<'_, f32, N>) {
+        for y in 0..sdf.height() {
+            for x in 0..sdf.width() {
+                if self.stencil.pixel(x, y)[0] & STENCIL_ERROR != 0 {
+                    // Set all color channels to the median.
+                    let texel = sdf.pixel_mut(x, y);
+                    let m = median3(texel);
+                    texel[..3].fill(m);
+                }
+            }
+        }
+    }
+}
+
+/// Predicts potential artifacts caused by the interpolation of the MSDF and corrects them by
+/// converting nearby texels to single-channel. Panics unless the bitmap has 3 or 4 channels.
+pub fn msdf_error_correction<const N: usize>(
+    sdf: BitmapMut<'_, f32, N>,
+    shape: &Shape,
+    transformation: &SDFTransformation,
+    config: &GeneratorConfig,
+) {
+    assert_msdf_channels::<N>();
+    let ec_config = &config.error_correction;
+    if ec_config.mode == ErrorCorrectionMode::Disabled {
+        return;
+    }
+    let mut ec = MSDFErrorCorrection::new(sdf.width(), sdf.height(), *transformation);
+    ec.min_deviation_ratio = ec_config.min_deviation_ratio;
+    ec.min_improve_ratio = ec_config.min_improve_ratio;
+    match ec_config.mode {
+        ErrorCorrectionMode::Disabled | ErrorCorrectionMode::Indiscriminate => {}
+        ErrorCorrectionMode::EdgePriority => {
+            ec.protect_corners(shape);
+            ec.protect_edges(sdf.as_ref());
+        }
+        ErrorCorrectionMode::EdgeOnly => ec.protect_all(),
+    }
+    if ec_config.distance_check_mode == DistanceCheckMode::DoNotCheckDistance
+        || (ec_config.distance_check_mode == DistanceCheckMode::CheckDistanceAtEdge
+            && ec_config.mode != ErrorCorrectionMode::EdgeOnly)
+    {
+        ec.find_errors(sdf.as_ref());
+        if ec_config.distance_check_mode == DistanceCheckMode::CheckDistanceAtEdge {
+            ec.protect_all();
+        }
+    }
+    if ec_config.distance_check_mode == DistanceCheckMode::AlwaysCheckDistance
+        || ec_config.distance_check_mode == DistanceCheckMode::CheckDistanceAtEdge
+    {
+        if config.overlap_support {
+            ec.find_errors_with_distance_check::<OverlappingContourCombiner<PseudoDistanceSelector>, N>(
+                sdf.as_ref(),
+                shape,
+            );
+        } else {
+            ec.find_errors_with_distance_check::<SimpleContourCombiner<PseudoDistanceSelector>, N>(
+                sdf.as_ref(),
+                shape,
+            );
+        }
+    }
+    ec.apply(sdf);
+}
+
+/// Applies the simplified error correction to all discontinuous distances (INDISCRIMINATE mode).
+/// Does not need shape or translation. Panics unless the bitmap has 3 or 4 channels.
+pub fn msdf_fast_distance_error_correction<const N: usize>(
+    sdf: BitmapMut<'_, f32, N>,
+    transformation: &SDFTransformation,
+    min_deviation_ratio: f64,
+) {
+    assert_msdf_channels::<N>();
+    let mut ec = MSDFErrorCorrection::new(sdf.width(), sdf.height(), *transformation);
+    ec.min_deviation_ratio = min_deviation_ratio;
+    ec.find_errors(sdf.as_ref());
+    ec.apply(sdf);
+}
+
+/// Applies the simplified error correction to edges only (EDGE_ONLY mode). Does not need shape or
+/// translation. Panics unless the bitmap has 3 or 4 channels.
+pub fn msdf_fast_edge_error_correction<const N: usize>(
+    sdf: BitmapMut<'_, f32, N>,
+    transformation: &SDFTransformation,
+    min_deviation_ratio: f64,
+) {
+    assert_msdf_channels::<N>();
+    let mut ec = MSDFErrorCorrection::new(sdf.width(), sdf.height(), *transformation);
+    ec.min_deviation_ratio = min_deviation_ratio;
+    ec.protect_all();
+    ec.find_errors(sdf.as_ref());
+    ec.apply(sdf);
+}
+
+fn detect_clash<const N: usize>(a: &[f32; N], b: &[f32; N], threshold: f64) -> bool {
+    // Sort channels so that pairs (a0, b0), (a1, b1), (a2, b2) go from biggest to smallest
+    // absolute difference.
+    let (mut a0, mut a1, mut a2) = (a[0], a[1], a[2]);
+    let (mut b0, mut b1, mut b2) = (b[0], b[1], b[2]);
+    if (b0 - a0).abs() < (b1 - a1).abs() {
+        std::mem::swap(&mut a0, &mut a1);
+        std::mem::swap(&mut b0, &mut b1);
+    }
+    if (b1 - a1).abs() < (b2 - a2).abs() {
+        std::mem::swap(&mut a1, &mut a2);
+        std::mem::swap(&mut b1, &mut b2);
+        if (b0 - a0).abs() < (b1 - a1).abs() {
+            std::mem::swap(&mut a0, &mut a1);
+            std::mem::swap(&mut b0, &mut b1);
+        }
+    }
+    ((b1 - a1).abs() as f64) >= threshold
+        // Ignore if other pixel has been equalized.
+        && !(b0 == b1 && b0 == b2)
+        // Out of the pair, only flag the pixel farther from a shape edge.
+        && (a2 - 0.5).abs() >= (b2 - 0.5).abs()
+}
+
+/// Flattens all texels that clash with any of the neighbors at the given offsets, each paired
+/// with its threshold.
+fn flatten_clashes<const N: usize>(
+    output: &mut BitmapMut<'_, f32, N>,
+    neighbors: &[(isize, isize, f64)],
+) {
+    let (w, h) = (output.width(), output.height());
+    let mut clashes = Vec::new();
+    for y in 0..h {
+        for x in 0..w {
+            let clash = neighbors.iter().any(|&(dx, dy, threshold)| {
+                let (nx, ny) = (x.wrapping_add_signed(dx), y.wrapping_add_signed(dy));
+                nx < w
+                    && ny < h
+                    && detect_clash(output.pixel(x, y), output.pixel(nx, ny), threshold)
+            });
+            if clash {
+                clashes.push((x, y));
+            }
+        }
+    }
+    for (x, y) in clashes {
+        let pixel = output.pixel_mut(x, y);
+        let med = median3(pixel);
+        pixel[..3].fill(med);
+    }
+}
+
+/// The original, simpler MSDF error correction, which flattens texels whose channels clash with
+/// an adjacent texel's by at least `threshold`, given in distance field values per texel
+/// horizontally and vertically. Panics unless the bitmap has 3 or 4 channels.
+pub fn msdf_error_correction_legacy<const N: usize>(
+    mut output: BitmapMut<'_, f32, N>,
+    threshold: Vector2,
+) {
+    assert_msdf_channels::<N>();
+    let (tx, ty) = (threshold.x, threshold.y);
+    flatten_clashes(
+        &mut output,
+        &[(-1, 0, tx), (1, 0, tx), (0, -1, ty), (0, 1, ty)],
+    );
+    let td = tx + ty;
+    flatten_clashes(
+        &mut output,
+        &[(-1, -1, td), (1, -1, td), (-1, 1, td), (1, 1, td)],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        edge_coloring_simple, generate_msdf, msdf_patch_edge_artifacts, Projection, Range,
+    };
+
+    const SIZE: usize = 16;
+
+    /// A square that fills a 16 by 16 pixel bitmap.
+    fn square() -> Shape {
+        let mut shape = crate::shape::tests::square(SIZE as f64);
+        edge_coloring_simple(&mut shape, 3.0, 0);
+        shape
+    }
+
+    fn transformation() -> SDFTransformation {
+        SDFTransformation::with_pixel_range(Projection::default(), Range::symmetrical(4.0))
+    }
+
+    fn clean_msdf(shape: &Shape) -> Bitmap<f32, 3> {
+        let mut config = GeneratorConfig::default();
+        config.error_correction.mode = ErrorCorrectionMode::Disabled;
+        let mut msdf = Bitmap::new(SIZE, SIZE);
+        generate_msdf(msdf.as_mut(), shape, &transformation(), &config);
+        msdf
+    }
+
+    /// Swaps two channels of a pair of texels in the middle of the square, where all channels
+    /// should be about 2.5. Interpolating between them drops the median to `low`: a value of 0.5
+    /// makes a false edge, while a higher value only distorts the distance.
+    fn add_artifact(msdf: &mut Bitmap<f32, 3>, low: f32) {
+        let (a, b) = (3.0, 2.0 * low - 3.0);
+        *msdf.pixel_mut(8, 8) = [a, b, 2.5];
+        *msdf.pixel_mut(9, 8) = [b, a, 2.5];
+    }
+
+    fn is_fixed(msdf: &Bitmap<f32, 3>) -> bool {
+        msdf.pixel(8, 8) == &[2.5; 3] && msdf.pixel(9, 8) == &[2.5; 3]
+    }
+
+    const MODES: [ErrorCorrectionMode; 3] = [
+        ErrorCorrectionMode::Indiscriminate,
+        ErrorCorrectionMode::EdgePriority,
+        ErrorCorrectionMode::EdgeOnly,
+    ];
+    const DISTANCE_CHECK_MODES: [DistanceCheckMode; 3] = [
+        DistanceCheckMode::DoNotCheckDistance,
+        DistanceCheckMode::CheckDistanceAtEdge,
+        DistanceCheckMode::AlwaysCheckDistance,
+    ];
+
+    fn corrected(
+        msdf: &Bitmap<f32, 3>,
+        shape: &Shape,
+        mode: ErrorCorrectionMode,
+        distance_check_mode: DistanceCheckMode,
+    ) -> Bitmap<f32, 3> {
+        let mut config = GeneratorConfig::default();
+        config.error_correction.mode = mode;
+        config.error_correction.distance_check_mode = distance_check_mode;
+        let mut msdf = msdf.clone();
+        msdf_error_correction(msdf.as_mut(), shape, &transformation(), &config);
+        msdf
+    }
+
+    #[test]
+    fn disabled_leaves_artifact() {
+        let shape = square();
+        let mut msdf = clean_msdf(&shape);
+        add_artifact(&mut msdf, 0.5);
+        for distance_check_mode in DISTANCE_CHECK_MODES {
+            let result = corrected(
+                &msdf,
+                &shape,
+                ErrorCorrectionMode::Disabled,
+                distance_check_mode,
+            );
+            assert!(result.pixels() == msdf.pixels());
+        }
+    }
+
+    #[test]
+    fn leaves_clean_msdf() {
+        let shape = square();
+        let msdf = clean_msdf(&shape);
+        for mode in MODES {
+            for distance_check_mode in DISTANCE_CHECK_MODES {
+                let result = corrected(&msdf, &shape, mode, distance_check_mode);
+                assert!(
+                    result.pixels() == msdf.pixels(),
+                    "{mode:?} {distance_check_mode:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fixes_false_edge() {
+        let shape = square();
+        let mut msdf = clean_msdf(&shape);
+        add_artifact(&mut msdf, 0.5);
+        for mode in MODES {
+            for distance_check_mode in DISTANCE_CHECK_MODES {
+                let result = corrected(&msdf, &shape, mode, distance_check_mode);
+                assert!(is_fixed(&result), "{mode:?} {distance_check_mode:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn fixes_distorted_distance_unless_edge_only() {
+        let shape = square();
+        let mut msdf = clean_msdf(&shape);
+        add_artifact(&mut msdf, 1.0);
+        for mode in MODES {
+            for distance_check_mode in DISTANCE_CHECK_MODES {
+                let result = corrected(&msdf, &shape, mode, distance_check_mode);
+                let expected = mode != ErrorCorrectionMode::EdgeOnly;
+                assert_eq!(
+                    is_fixed(&result),
+                    expected,
+                    "{mode:?} {distance_check_mode:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fast_error_correction() {
+        let shape = square();
+        let mut msdf = clean_msdf(&shape);
+        add_artifact(&mut msdf, 1.0);
+        let ratio = ErrorCorrectionConfig::DEFAULT_MIN_DEVIATION_RATIO;
+
+        let mut result = msdf.clone();
+        msdf_fast_distance_error_correction(result.as_mut(), &transformation(), ratio);
+        assert!(is_fixed(&result));
+
+        let mut result = msdf.clone();
+        msdf_fast_edge_error_correction(result.as_mut(), &transformation(), ratio);
+        assert!(!is_fixed(&result));
+        add_artifact(&mut result, 0.5);
+        msdf_fast_edge_error_correction(result.as_mut(), &transformation(), ratio);
+        assert!(is_fixed(&result));
+    }
+
+    #[test]
+    #[should_panic(expected = "3 or 4 channels")]
+    fn rejects_two_channels() {
+        let shape = square();
+        let mut sdf = Bitmap::<f32, 2>::new(SIZE, SIZE);
+        msdf_error_correction(
+            sdf.as_mut(),
+            &shape,
+            &transformation(),
+            &GeneratorConfig::default(),
+        );
+    }
+
+    #[test]
+    fn legacy_error_correction() {
+        let shape = square();
+        let mut msdf = clean_msdf(&shape);
+        let mut result = msdf.clone();
+        msdf_error_correction_legacy(result.as_mut(), Vector2::new(1.0, 1.0));
+        assert!(result.pixels() == msdf.pixels());
+        add_artifact(&mut msdf, 1.0);
+        msdf_error_correction_legacy(msdf.as_mut(), Vector2::new(1.0, 1.0));
+        assert!(is_fixed(&msdf));
+    }
+
+    #[test]
+    fn patch_edge_artifacts() {
+        let shape = square();
+        let config = GeneratorConfig::default();
+        let mut msdf = clean_msdf(&shape);
+        let mut result = msdf.clone();
+        msdf_patch_edge_artifacts(result.as_mut(), &shape, &transformation(), &config);
+        assert!(result.pixels() == msdf.pixels());
+        add_artifact(&mut msdf, 0.25);
+        msdf_patch_edge_artifacts(msdf.as_mut(), &shape, &transformation(), &config);
+        // One of the texels is flattened, which removes the false edge between them.
+        let midpoint = interpolated_median(msdf.pixel(8, 8), msdf.pixel(9, 8), 0.5);
+        assert!(midpoint > 0.5, "{midpoint}");
+    }
+}
@@ -0,0 +1,254 @@
+use crate::{
+    msdf_error_correction, BitmapMut, ContourCombiner, DistanceMapping, EdgeSelector,
+    ErrorCorrectionConfig, MultiAndTrueDistance, MultiAndTrueDistanceSelector, MultiDistance,
+    MultiDistanceSelector, OverlappingContourCombiner, PseudoDistanceSelector, SDFTransformation,
+    Shape, ShapeDistanceFinder, SimpleContourCombiner, TrueDistanceSelector, Vector2,
+};
+
+#[derive(Copy, Clone, Debug)]
+pub struct GeneratorConfig {
+    /// Whether to resolve overlapping contours correctly. Disabling it is slightly faster, but
+    /// only produces correct results for shapes without self-intersections or overlaps.
+    pub overlap_support: bool,
+    /// The error correction applied by [`generate_msdf`] and [`generate_mtsdf`].
+    pub error_correction: ErrorCorrectionConfig,
+}
+
+impl Default for GeneratorConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            overlap_support: true,
+            error_correction: ErrorCorrectionConfig::default(),
+        }
+    }
+}
+
+trait DistancePixelConversion<const N: usize> {
+    fn convert(&self, pixel: &mut [f32; N], mapping: &DistanceMapping);
+}
+
+impl DistancePixelConversion<1> for f64 {
+    #[inline]
+    fn convert(&self, pixel: &mut [f32; 1], mapping: &DistanceMapping) {
+        pixel[0] = mapping.map(*self) as f32;
+    }
+}
+
+impl DistancePixelConversion<3> for MultiDistance {
+    #[inline]
+    fn convert(&self, pixel: &mut [f32; 3], mapping: &DistanceMapping) {
+        pixel[0] = mapping.map(self.r) as f32;
+        pixel[1] = mapping.map(self.g) as f32;
+        pixel[2] = mapping.map(self.b) as f32;
+    }
+}
+
+impl DistancePixelConversion<4> for MultiAndTrueDistance {
+    #[inline]
+    fn convert(&self, pixel: &mut [f32; 4], mapping: &DistanceMapping) {
+        pixel[0] = mapping.map(self.multi.r) as f32;
+        pixel[1] = mapping.map(self.multi.g) as f32;
+        pixel[2] = mapping.map(self.multi.b) as f32;
+        pixel[3] = mapping.map(self.a) as f32;
+    }
+}
+
+fn generate_distance_field<C, const N: usize>(
+    mut output: BitmapMut<f32, N>,
+    shape: &Shape,
+    transformation: &SDFTransformation,
+) where
+    C: ContourCombiner,
+    <C::Selector as EdgeSelector>::Distance: DistancePixelConversion<N>,
+{
+    let (width, height) = (output.width(), output.height());
+    let mut distance_finder = ShapeDistanceFinder::<C>::new(shape);
+    let mut right_to_left = false;
+    for y in 0..height {
+        let row = if shape.inverse_y_axis {
+            height - y - 1
+        } else {
+            y
+        };
+        for col in 0..width {
+            let x = if right_to_left { width - col - 1 } else { col };
+            let p = transformation
+                .projection
+                .unproject(Vector2::new(x as f64 + 0.5, y as f64 + 0.5));
+            let distance = distance_finder.distance(p);
+            distance.convert(output.pixel_mut(x, row), &transformation.distance_mapping);
+        }
+        right_to_left = !right_to_left;
+    }
+}
+
+fn generate_with_selector<S, const N: usize>(
+    output: BitmapMut<f32, N>,
+    shape: &Shape,
+    transformation: &SDFTransformation,
+    config: &GeneratorConfig,
+) where
+    S: EdgeSelector,
+    S::Distance: DistancePixelConversion<N>,
+{
+    if config.overlap_support {
+        generate_distance_field::<OverlappingContourCombiner<S>, N>(output, shape, transformation);
+    } else {
+        generate_distance_field::<SimpleContourCombiner<S>, N>(output, shape, transformation);
+    }
+}
+
+/// Generates a conventional single-channel signed distance field.
+pub fn generate_sdf(
+    output: BitmapMut<f32, 1>,
+    shape: &Shape,
+    transformation: &SDFTransformation,
+    config: &GeneratorConfig,
+) {
+    generate_with_selector::<TrueDistanceSelector, 1>(output, shape, transformation, config);
+}
+
+/// Generates a single-channel signed pseudo-distance field.
+pub fn generate_psdf(
+    output: BitmapMut<f32, 1>,
+    shape: &Shape,
+    transformation: &SDFTransformation,
+    config: &GeneratorConfig,
+) {
+    generate_with_selector::<PseudoDistanceSelector, 1>(output, shape, transformation, config);
+}
+
+/// Generates a multi-channel signed distance field. Edge colors have to be assigned first,
+/// e.g. using [`edge_coloring_simple`](crate::edge_coloring_simple).
+pub fn generate_msdf(
+    mut output: BitmapMut<f32, 3>,
+    shape: &Shape,
+    transformation: &SDFTransformation,
+    config: &GeneratorConfig,
+) {
+    generate_with_selector::<MultiDistanceSelector, 3>(
+        output.as_mut(),
+        shape,
+        transformation,
+        config,
+    );
+    msdf_error_correction(output, shape, transformation, config);
+}
+
+/// Generates a multi-channel signed distance field with the true signed distance in the alpha
+/// channel. Edge colors have to be assigned first.
+pub fn generate_mtsdf(
+    mut output: BitmapMut<f32, 4>,
+    shape: &Shape,
+    transformation: &SDFTransformation,
+    config: &GeneratorConfig,
+) {
+    generate_with_selector::<MultiAndTrueDistanceSelector, 4>(
+        output.as_mut(),
+        shape,
+        transformation,
+        config,
+    );
+    msdf_error_correction(output, shape, transformation, config);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::tests::add_rectangle;
+    use crate::{edge_coloring_simple, median, Bitmap, Projection, Range};
+
+    const SIZE: usize = 8;
+
+    /// A square from 2 to 6 in an 8 by 8 pixel bitmap.
+    fn square() -> Shape {
+        let mut shape = Shape::default();
+        add_rectangle(&mut shape, 2.0, 2.0, 6.0, 6.0);
+        shape
+    }
+
+    /// Maps distances from -1 to 1 pixel to the interval from 0 to 1.
+    fn transformation() -> SDFTransformation {
+        SDFTransformation::with_pixel_range(Projection::default(), Range::symmetrical(2.0))
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-6, "{a} != {b}");
+    }
+
+    /// Checks that the field is positive inside the square and crosses 0.5 on its left edge,
+    /// which lies halfway between the centers of the texels at x = 1 and x = 2.
+    fn assert_square_field(field: impl Fn(usize, usize) -> f32) {
+        for y in 2..6 {
+            assert_close(field(1, y), 0.25);
+            assert_close(field(2, y), 0.75);
+        }
+        assert_close(field(4, 4), 1.25);
+        assert!(field(7, 7) < 0.0);
+    }
+
+    #[test]
+    fn generates_psdf() {
+        let mut psdf = Bitmap::<f32, 1>::new(SIZE, SIZE);
+        generate_psdf(
+            psdf.as_mut(),
+            &square(),
+            &transformation(),
+            &GeneratorConfig::default(),
+        );
+        assert_square_field(|x, y| psdf.pixel(x, y)[0]);
+        // Past the corner, the pseudo-distance is the distance to the extended edge.
+        assert_close(psdf.pixel(0, 0)[0], -0.25);
+    }
+
+    #[test]
+    fn generates_sdf() {
+        let mut sdf = Bitmap::<f32, 1>::new(SIZE, SIZE);
+        generate_sdf(
+            sdf.as_mut(),
+            &square(),
+            &transformation(),
+            &GeneratorConfig::default(),
+        );
+        assert_square_field(|x, y| sdf.pixel(x, y)[0]);
+        // Past the corner, the true distance is the distance to the corner.
+        assert_close(sdf.pixel(0, 0)[0], 0.5 - 0.75 * 2f32.sqrt());
+    }
+
+    #[test]
+    fn generates_msdf() {
+        let mut shape = square();
+        edge_coloring_simple(&mut shape, 3.0, 0);
+        let mut msdf = Bitmap::<f32, 3>::new(SIZE, SIZE);
+        generate_msdf(
+            msdf.as_mut(),
+            &shape,
+            &transformation(),
+            &GeneratorConfig::default(),
+        );
+        assert_square_field(|x, y| {
+            let [r, g, b] = *msdf.pixel(x, y);
+            median(r, g, b)
+        });
+    }
+
+    #[test]
+    fn generates_mtsdf() {
+        let mut shape = square();
+        edge_coloring_simple(&mut shape, 3.0, 0);
+        let mut mtsdf = Bitmap::<f32, 4>::new(SIZE, SIZE);
+        generate_mtsdf(
+            mtsdf.as_mut(),
+            &shape,
+            &transformation(),
+            &GeneratorConfig::default(),
+        );
+        assert_square_field(|x, y| {
+            let [r, g, b, _] = *mtsdf.pixel(x, y);
+            median(r, g, b)
+        });
+        assert_square_field(|x, y| mtsdf.pixel(x, y)[3]);
+        assert_close(mtsdf.pixel(0, 0)[3], 0.5 - 0.75 * 2f32.sqrt());
+    }
+}
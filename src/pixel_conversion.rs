@@ -0,0 +1,226 @@
+use crate::{clamp_0_1, BitmapMut, BitmapRef};
+
+#[inline]
+pub fn pixel_float_to_byte(x: f32) -> u8 {
+    (255.0 * clamp_0_1(x) + 0.5) as u8
+}
+
+#[inline]
+pub fn pixel_byte_to_float(x: u8) -> f32 {
+    1.0 / 255.0 * x as f32
+}
+
+/// Rounds to the nearest of the 65536 levels, since `f32` is too coarse near 1 to truncate
+/// `65536 * x` without breaking the round trip through [`pixel_u16_to_float`].
+#[inline]
+pub fn pixel_float_to_u16(x: f32) -> u16 {
+    (65535.0 * clamp_0_1(x) + 0.5) as u16
+}
+
+#[inline]
+pub fn pixel_u16_to_float(x: u16) -> f32 {
+    1.0 / 65535.0 * x as f32
+}
+
+#[inline]
+fn assert_same_size<S, D, const N: usize, const M: usize>(
+    output: &BitmapMut<'_, D, M>,
+    input: &BitmapRef<'_, S, N>,
+) {
+    assert!(
+        output.width() == input.width() && output.height() == input.height(),
+        "bitmap size mismatch"
+    );
+}
+
+fn convert_pixels<S: Copy, D, const N: usize>(
+    mut output: BitmapMut<'_, D, N>,
+    input: BitmapRef<'_, S, N>,
+    convert: impl Fn(S) -> D,
+) {
+    assert_same_size(&output, &input);
+    for y in 0..input.height() {
+        for x in 0..input.width() {
+            let src = input.pixel(x, y);
+            for (dst, &src) in output.pixel_mut(x, y).iter_mut().zip(src) {
+                *dst = convert(src);
+            }
+        }
+    }
+}
+
+/// Converts a float bitmap to 8 bits per channel using [`pixel_float_to_byte`].
+pub fn convert_float_to_byte<const N: usize>(
+    output: BitmapMut<'_, u8, N>,
+    input: BitmapRef<'_, f32, N>,
+) {
+    convert_pixels(output, input, pixel_float_to_byte);
+}
+
+/// Converts an 8-bit bitmap to floats using [`pixel_byte_to_float`].
+pub fn convert_byte_to_float<const N: usize>(
+    output: BitmapMut<'_, f32, N>,
+    input: BitmapRef<'_, u8, N>,
+) {
+    convert_pixels(output, input, pixel_byte_to_float);
+}
+
+/// Converts a float bitmap to 16 bits per channel using [`pixel_float_to_u16`].
+pub fn convert_float_to_u16<const N: usize>(
+    output: BitmapMut<'_, u16, N>,
+    input: BitmapRef<'_, f32, N>,
+) {
+    convert_pixels(output, input, pixel_float_to_u16);
+}
+
+/// Converts a 16-bit bitmap to floats using [`pixel_u16_to_float`].
+pub fn convert_u16_to_float<const N: usize>(
+    output: BitmapMut<'_, f32, N>,
+    input: BitmapRef<'_, u16, N>,
+) {
+    convert_pixels(output, input, pixel_u16_to_float);
+}
+
+fn map_channels<T: Copy, const N: usize, const M: usize>(
+    mut output: BitmapMut<'_, T, M>,
+    input: BitmapRef<'_, T, N>,
+    channel: impl Fn(&[T; N], usize) -> T,
+) {
+    assert_same_size(&output, &input);
+    for y in 0..input.height() {
+        for x in 0..input.width() {
+            let src = input.pixel(x, y);
+            for (i, dst) in output.pixel_mut(x, y).iter_mut().enumerate() {
+                *dst = channel(src, i);
+            }
+        }
+    }
+}
+
+/// Rearranges the channels of `input` into `output`. Each output channel takes the input
+/// channel at the index given by `channels`.
+pub fn swizzle<T: Copy, const N: usize, const M: usize>(
+    output: BitmapMut<'_, T, M>,
+    input: BitmapRef<'_, T, N>,
+    channels: [usize; M],
+) {
+    assert!(
+        channels.iter().all(|&c| c < N),
+        "swizzle channel out of range"
+    );
+    map_channels(output, input, |src, i| src[channels[i]]);
+}
+
+/// Expands 3-channel pixels to 4 channels. Each output channel takes the input channel at the
+/// index given by `channels`, or `fill` if it is `None`.
+fn swizzle_with_fill<T: Copy>(
+    output: BitmapMut<'_, T, 4>,
+    input: BitmapRef<'_, T, 3>,
+    channels: [Option<usize>; 4],
+    fill: T,
+) {
+    map_channels(output, input, |src, i| channels[i].map_or(fill, |c| src[c]));
+}
+
+/// Expands RGB pixels to RGBA, setting the alpha channel to `alpha`.
+pub fn rgb_to_rgba<T: Copy>(output: BitmapMut<'_, T, 4>, input: BitmapRef<'_, T, 3>, alpha: T) {
+    swizzle_with_fill(output, input, [Some(0), Some(1), Some(2), None], alpha);
+}
+
+/// Expands RGB pixels to BGRA, setting the alpha channel to `alpha`.
+pub fn rgb_to_bgra<T: Copy>(output: BitmapMut<'_, T, 4>, input: BitmapRef<'_, T, 3>, alpha: T) {
+    swizzle_with_fill(output, input, [Some(2), Some(1), Some(0), None], alpha);
+}
+
+/// Swaps the red and blue channels of RGBA pixels. Works in both directions.
+pub fn rgba_to_bgra<T: Copy>(output: BitmapMut<'_, T, 4>, input: BitmapRef<'_, T, 4>) {
+    swizzle(output, input, [2, 1, 0, 3]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bitmap, RowOrder};
+
+    fn rgb() -> Bitmap<u8, 3> {
+        Bitmap::from_pixels(vec![1, 2, 3, 4, 5, 6], 2, 1, RowOrder::BottomUp)
+    }
+
+    #[test]
+    fn clamps_float_to_byte() {
+        assert_eq!(pixel_float_to_byte(0.0), 0);
+        assert_eq!(pixel_float_to_byte(0.5), 128);
+        assert_eq!(pixel_float_to_byte(1.0), 255);
+        assert_eq!(pixel_float_to_byte(-0.5), 0);
+        assert_eq!(pixel_float_to_byte(1.5), 255);
+    }
+
+    #[test]
+    fn clamps_float_to_u16() {
+        assert_eq!(pixel_float_to_u16(0.0), 0);
+        assert_eq!(pixel_float_to_u16(0.5), 32768);
+        assert_eq!(pixel_float_to_u16(1.0), 65535);
+        assert_eq!(pixel_float_to_u16(-0.5), 0);
+        assert_eq!(pixel_float_to_u16(1.5), 65535);
+    }
+
+    #[test]
+    fn round_trips_bytes() {
+        let bytes: Vec<u8> = (0..=u8::MAX).collect();
+        let input = Bitmap::<u8, 1>::from_pixels(bytes.clone(), 16, 16, RowOrder::BottomUp);
+        let mut floats = Bitmap::<f32, 1>::new(16, 16);
+        let mut output = Bitmap::<u8, 1>::new(16, 16);
+        convert_byte_to_float(floats.as_mut(), input.as_ref());
+        convert_float_to_byte(output.as_mut(), floats.as_ref());
+        assert_eq!(floats.pixel(0, 0), &[0.0]);
+        assert_eq!(floats.pixel(15, 15), &[1.0]);
+        assert_eq!(output.pixels(), &bytes[..]);
+    }
+
+    #[test]
+    fn round_trips_u16() {
+        let values: Vec<u16> = (0..=u16::MAX).collect();
+        let input = Bitmap::<u16, 1>::from_pixels(values.clone(), 256, 256, RowOrder::BottomUp);
+        let mut floats = Bitmap::<f32, 1>::new(256, 256);
+        let mut output = Bitmap::<u16, 1>::new(256, 256);
+        convert_u16_to_float(floats.as_mut(), input.as_ref());
+        convert_float_to_u16(output.as_mut(), floats.as_ref());
+        assert_eq!(floats.pixel(255, 255), &[1.0]);
+        assert_eq!(output.pixels(), &values[..]);
+    }
+
+    #[test]
+    fn orders_channels() {
+        let input = rgb();
+        let mut output = Bitmap::<u8, 4>::new(2, 1);
+        rgb_to_rgba(output.as_mut(), input.as_ref(), 255);
+        assert_eq!(output.pixels(), &[1, 2, 3, 255, 4, 5, 6, 255]);
+        rgb_to_bgra(output.as_mut(), input.as_ref(), 0);
+        assert_eq!(output.pixels(), &[3, 2, 1, 0, 6, 5, 4, 0]);
+        let bgra = output.clone();
+        rgba_to_bgra(output.as_mut(), bgra.as_ref());
+        assert_eq!(output.pixels(), &[1, 2, 3, 0, 4, 5, 6, 0]);
+    }
+
+    #[test]
+    fn swizzles_channels() {
+        let input = rgb();
+        let mut output = Bitmap::<u8, 2>::new(2, 1);
+        swizzle(output.as_mut(), input.as_ref(), [2, 2]);
+        assert_eq!(output.pixels(), &[3, 3, 6, 6]);
+    }
+
+    #[test]
+    #[should_panic(expected = "bitmap size mismatch")]
+    fn rejects_mismatched_sizes() {
+        let mut output = Bitmap::<u8, 3>::new(1, 2);
+        swizzle(output.as_mut(), rgb().as_ref(), [0, 1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "swizzle channel out of range")]
+    fn rejects_out_of_range_channels() {
+        let mut output = Bitmap::<u8, 1>::new(2, 1);
+        swizzle(output.as_mut(), rgb().as_ref(), [3]);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{Bounds, Vector2};
+
+/// A transformation from shape coordinates to pixel coordinates.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Projection {
+    pub scale: Vector2,
+    pub translate: Vector2,
+}
+
+impl Default for Projection {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Vector2::new(1.0, 1.0), Vector2::new(0.0, 0.0))
+    }
+}
+
+impl Projection {
+    #[inline]
+    pub const fn new(scale: Vector2, translate: Vector2) -> Self {
+        Self { scale, translate }
+    }
+
+    /// Fits `bounds` into the center of a `width` by `height` pixel frame, keeping `padding`
+    /// pixels free on every side. Returns `None` if the padding leaves no room for the shape.
+    pub fn fit_bounds(bounds: &Bounds, width: f64, height: f64, padding: f64) -> Option<Self> {
+        let (mut l, mut b, mut r, mut t) = (bounds.l, bounds.b, bounds.r, bounds.t);
+        if l >= r || b >= t {
+            l = 0.0;
+            b = 0.0;
+            r = 1.0;
+            t = 1.0;
+        }
+        let frame = Vector2::new(width - 2.0 * padding, height - 2.0 * padding);
+        if frame.x <= 0.0 || frame.y <= 0.0 {
+            return None;
+        }
+        let dims = Vector2::new(r - l, t - b);
+        let (scale, translate) = if dims.x * frame.y < dims.y * frame.x {
+            (
+                frame.y / dims.y,
+                Vector2::new(0.5 * (frame.x / frame.y * dims.y - dims.x) - l, -b),
+            )
+        } else {
+            (
+                frame.x / dims.x,
+                Vector2::new(-l, 0.5 * (frame.y / frame.x * dims.x - dims.y) - b),
+            )
+        };
+        Some(Self::new(
+            Vector2::from(scale),
+            translate + Vector2::from(padding / scale),
+        ))
+    }
+
+    #[inline]
+    pub fn project(&self, coord: Vector2) -> Vector2 {
+        self.scale * (coord + self.translate)
+    }
+
+    #[inline]
+    pub fn unproject(&self, coord: Vector2) -> Vector2 {
+        coord / self.scale - self.translate
+    }
+
+    #[inline]
+    pub fn project_vector(&self, vector: Vector2) -> Vector2 {
+        self.scale * vector
+    }
+
+    #[inline]
+    pub fn unproject_vector(&self, vector: Vector2) -> Vector2 {
+        vector / self.scale
+    }
+
+    #[inline]
+    pub fn project_x(&self, x: f64) -> f64 {
+        self.scale.x * (x + self.translate.x)
+    }
+
+    #[inline]
+    pub fn project_y(&self, y: f64) -> f64 {
+        self.scale.y * (y + self.translate.y)
+    }
+
+    #[inline]
+    pub fn unproject_x(&self, x: f64) -> f64 {
+        x / self.scale.x - self.translate.x
+    }
+
+    #[inline]
+    pub fn unproject_y(&self, y: f64) -> f64 {
+        y / self.scale.y - self.translate.y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fit(l: f64, b: f64, r: f64, t: f64, size: f64, padding: f64) -> Option<Projection> {
+        Projection::fit_bounds(&Bounds::new(l, b, r, t), size, size, padding)
+    }
+
+    #[test]
+    fn centers_wide_bounds_vertically() {
+        let projection = fit(0.0, 0.0, 2.0, 1.0, 10.0, 1.0).unwrap();
+        assert_eq!(projection.scale, Vector2::new(4.0, 4.0));
+        assert_eq!(
+            projection.project(Vector2::new(0.0, 0.0)),
+            Vector2::new(1.0, 3.0)
+        );
+        assert_eq!(
+            projection.project(Vector2::new(2.0, 1.0)),
+            Vector2::new(9.0, 7.0)
+        );
+    }
+
+    #[test]
+    fn centers_tall_bounds_horizontally() {
+        let projection = fit(0.0, 0.0, 1.0, 2.0, 10.0, 0.0).unwrap();
+        assert_eq!(projection.scale, Vector2::new(5.0, 5.0));
+        assert_eq!(
+            projection.project(Vector2::new(0.0, 0.0)),
+            Vector2::new(2.5, 0.0)
+        );
+        assert_eq!(
+            projection.project(Vector2::new(1.0, 2.0)),
+            Vector2::new(7.5, 10.0)
+        );
+    }
+
+    #[test]
+    fn fits_degenerate_bounds_as_unit_square() {
+        for projection in [
+            fit(1.0, 1.0, 1.0, 1.0, 4.0, 0.0),
+            fit(f64::MAX, f64::MAX, -f64::MAX, -f64::MAX, 4.0, 0.0),
+        ] {
+            assert_eq!(
+                projection,
+                Some(Projection::new(
+                    Vector2::new(4.0, 4.0),
+                    Vector2::new(0.0, 0.0)
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_padding_without_room() {
+        assert_eq!(fit(0.0, 0.0, 1.0, 1.0, 10.0, 5.0), None);
+        let bounds = Bounds::new(0.0, 0.0, 1.0, 1.0);
+        assert_eq!(Projection::fit_bounds(&bounds, 10.0, 4.0, 2.5), None);
+    }
+
+    #[test]
+    fn unprojects_projected_coordinates() {
+        let projection = Projection::new(Vector2::new(2.0, 4.0), Vector2::new(0.5, -1.0));
+        let p = Vector2::new(1.25, -3.0);
+        assert_eq!(projection.project(p), Vector2::new(3.5, -16.0));
+        assert_eq!(projection.unproject(projection.project(p)), p);
+        assert_eq!(projection.unproject_vector(projection.project_vector(p)), p);
+        assert_eq!(projection.project_x(p.x), 3.5);
+        assert_eq!(projection.unproject_y(projection.project_y(p.y)), p.y);
+    }
+}
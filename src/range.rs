@@ -0,0 +1,65 @@
+use std::ops::{Div, DivAssign, Mul, MulAssign};
+
+/// A range of signed distances, from `lower` to `upper`.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Range {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Range {
+    #[inline]
+    pub const fn new(lower: f64, upper: f64) -> Self {
+        Self { lower, upper }
+    }
+
+    /// A range of the given total width, centered around zero.
+    #[inline]
+    pub fn symmetrical(width: f64) -> Self {
+        Self::new(-0.5 * width, 0.5 * width)
+    }
+
+    #[inline]
+    pub fn width(&self) -> f64 {
+        self.upper - self.lower
+    }
+}
+
+impl From<f64> for Range {
+    #[inline]
+    fn from(val: f64) -> Self {
+        Self::symmetrical(val)
+    }
+}
+
+impl Mul<f64> for Range {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: f64) -> Self::Output {
+        Self::new(self.lower * rhs, self.upper * rhs)
+    }
+}
+
+impl MulAssign<f64> for Range {
+    #[inline]
+    fn mul_assign(&mut self, rhs: f64) {
+        self.lower *= rhs;
+        self.upper *= rhs;
+    }
+}
+
+impl Div<f64> for Range {
+    type Output = Self;
+    #[inline]
+    fn div(self, rhs: f64) -> Self::Output {
+        Self::new(self.lower / rhs, self.upper / rhs)
+    }
+}
+
+impl DivAssign<f64> for Range {
+    #[inline]
+    fn div_assign(&mut self, rhs: f64) {
+        self.lower /= rhs;
+        self.upper /= rhs;
+    }
+}
@@ -0,0 +1,371 @@
+use crate::{
+    clamp_0_1, interpolate, median, BitmapMut, BitmapRef, DistanceMapping, FillRule, Projection,
+    Range, Scanline, Shape, Vector2,
+};
+
+/// Rasterizes the shape into a binary mask, where each pixel is 1 if its center is inside the
+/// shape according to `fill_rule`, and 0 otherwise.
+pub fn rasterize(
+    mut output: BitmapMut<'_, f32, 1>,
+    shape: &Shape,
+    projection: &Projection,
+    fill_rule: FillRule,
+) {
+    let (w, h) = (output.width(), output.height());
+    let mut scanline = Scanline::new();
+    for y in 0..h {
+        let row = if shape.inverse_y_axis { h - y - 1 } else { y };
+        shape.scanline(&mut scanline, projection.unproject_y(y as f64 + 0.5));
+        for x in 0..w {
+            let fill = scanline.filled(projection.unproject_x(x as f64 + 0.5), fill_rule);
+            output.pixel_mut(x, row)[0] = fill as u8 as f32;
+        }
+    }
+}
+
+/// Rasterizes the shape into a coverage mask by sampling a regular grid of
+/// `samples` × `samples` points within each pixel, and storing the fraction that is inside the
+/// shape according to `fill_rule`.
+pub fn rasterize_supersampled(
+    mut output: BitmapMut<'_, f32, 1>,
+    shape: &Shape,
+    projection: &Projection,
+    fill_rule: FillRule,
+    samples: usize,
+) {
+    assert!(samples > 0, "at least one sample per axis is required");
+    let (w, h) = (output.width(), output.height());
+    let step = 1.0 / samples as f64;
+    let weight = 1.0 / (samples * samples) as f32;
+    let mut scanline = Scanline::new();
+    for y in 0..h {
+        let row = if shape.inverse_y_axis { h - y - 1 } else { y };
+        for x in 0..w {
+            output.pixel_mut(x, row)[0] = 0.0;
+        }
+        for sy in 0..samples {
+            let sample_y = y as f64 + (sy as f64 + 0.5) * step;
+            shape.scanline(&mut scanline, projection.unproject_y(sample_y));
+            for x in 0..w {
+                let mut count = 0;
+                for sx in 0..samples {
+                    let sample_x = x as f64 + (sx as f64 + 0.5) * step;
+                    if scanline.filled(projection.unproject_x(sample_x), fill_rule) {
+                        count += 1;
+                    }
+                }
+                output.pixel_mut(x, row)[0] += count as f32 * weight;
+            }
+        }
+    }
+}
+
+#[inline]
+fn dist_val(dist: f32, mapping: &DistanceMapping) -> f32 {
+    clamp_0_1(mapping.map(dist as f64) + 0.5) as f32
+}
+
+/// Reconstructs the shape's appearance from its distance field, resampled to the size of
+/// `output`. `sdf_px_range` is the distance range of the field in its own pixels; if it is
+/// empty, a hard-edged image is produced instead of an anti-aliased one. The output either
+/// has the same number of channels as the field, which are rendered separately, or a single
+/// channel, which renders the median of a 3 or 4-channel field.
+pub fn render_sdf<const M: usize, const N: usize>(
+    mut output: BitmapMut<'_, f32, M>,
+    sdf: BitmapRef<'_, f32, N>,
+    mut sdf_px_range: Range,
+    sdf_zero_value: f32,
+) {
+    let median_only = match (M, N) {
+        (1, 3) | (1, 4) => true,
+        _ if M == N => false,
+        _ => panic!("cannot render a {N}-channel distance field into {M} channels"),
+    };
+    let (w, h) = (output.width(), output.height());
+    let scale = Vector2::new(
+        sdf.width() as f64 / w as f64,
+        sdf.height() as f64 / h as f64,
+    );
+    let binary = sdf_px_range.lower == sdf_px_range.upper;
+    sdf_px_range *= (w + h) as f64 / (sdf.width() + sdf.height()) as f64;
+    let distance_mapping = DistanceMapping::inverse_of(sdf_px_range);
+    let sdf_bias = 0.5 - sdf_zero_value;
+    let render = |sd: f32| {
+        if binary {
+            (sd >= sdf_zero_value) as u8 as f32
+        } else {
+            dist_val(sd + sdf_bias, &distance_mapping)
+        }
+    };
+    for y in 0..h {
+        for x in 0..w {
+            let sd = interpolate(sdf, scale * Vector2::new(x as f64 + 0.5, y as f64 + 0.5));
+            let pixel = output.pixel_mut(x, y);
+            if median_only {
+                pixel[0] = render(median(sd[0], sd[1], sd[2]));
+            } else {
+                for (value, sd) in pixel.iter_mut().zip(sd) {
+                    *value = render(sd);
+                }
+            }
+        }
+    }
+}
+
+fn single_distance_sign_correction<const N: usize>(
+    mut sdf: BitmapMut<'_, f32, N>,
+    shape: &Shape,
+    projection: &Projection,
+    sdf_zero_value: f32,
+    fill_rule: FillRule,
+) {
+    let (w, h) = (sdf.width(), sdf.height());
+    let double_sdf_zero_value = sdf_zero_value + sdf_zero_value;
+    let mut scanline = Scanline::new();
+    for y in 0..h {
+        let row = if shape.inverse_y_axis { h - y - 1 } else { y };
+        shape.scanline(&mut scanline, projection.unproject_y(y as f64 + 0.5));
+        for x in 0..w {
+            let fill = scanline.filled(projection.unproject_x(x as f64 + 0.5), fill_rule);
+            let sd = &mut sdf.pixel_mut(x, row)[0];
+            if (*sd > sdf_zero_value) != fill {
+                *sd = double_sdf_zero_value - *sd;
+            }
+        }
+    }
+}
+
+fn multi_distance_sign_correction<const N: usize>(
+    mut sdf: BitmapMut<'_, f32, N>,
+    shape: &Shape,
+    projection: &Projection,
+    sdf_zero_value: f32,
+    fill_rule: FillRule,
+) {
+    let (w, h) = (sdf.width(), sdf.height());
+    if w == 0 || h == 0 {
+        return;
+    }
+    let double_sdf_zero_value = sdf_zero_value + sdf_zero_value;
+    let mut scanline = Scanline::new();
+    let mut ambiguous = false;
+    let mut match_map = vec![0i8; w * h];
+    for y in 0..h {
+        let row = if shape.inverse_y_axis { h - y - 1 } else { y };
+        shape.scanline(&mut scanline, projection.unproject_y(y as f64 + 0.5));
+        for x in 0..w {
+            let fill = scanline.filled(projection.unproject_x(x as f64 + 0.5), fill_rule);
+            let msd = sdf.pixel_mut(x, row);
+            let sd = median(msd[0], msd[1], msd[2]);
+            if sd == sdf_zero_value {
+                ambiguous = true;
+            } else if (sd > sdf_zero_value) != fill {
+                for channel in &mut msd[..3] {
+                    *channel = double_sdf_zero_value - *channel;
+                }
+                match_map[y * w + x] = -1;
+            } else {
+                match_map[y * w + x] = 1;
+            }
+            if N >= 4 && (msd[3] > sdf_zero_value) != fill {
+                msd[3] = double_sdf_zero_value - msd[3];
+            }
+        }
+    }
+    // This step is necessary to avoid artifacts when whole shape is inverted
+    if ambiguous {
+        for y in 0..h {
+            let row = if shape.inverse_y_axis { h - y - 1 } else { y };
+            for x in 0..w {
+                if match_map[y * w + x] != 0 {
+                    continue;
+                }
+                let mut neighbor_match = 0;
+                if x > 0 {
+                    neighbor_match += match_map[y * w + x - 1] as i32;
+                }
+                if x < w - 1 {
+                    neighbor_match += match_map[y * w + x + 1] as i32;
+                }
+                if y > 0 {
+                    neighbor_match += match_map[(y - 1) * w + x] as i32;
+                }
+                if y < h - 1 {
+                    neighbor_match += match_map[(y + 1) * w + x] as i32;
+                }
+                if neighbor_match < 0 {
+                    for channel in &mut sdf.pixel_mut(x, row)[..3] {
+                        *channel = double_sdf_zero_value - *channel;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Fixes the sign of the distance field so that it agrees with the shape's fill, as determined
+/// by scanlining it with `fill_rule`. Texels are flipped around `sdf_zero_value`, the value that
+/// represents zero distance (usually 0.5). Supports bitmaps with 1, 3 or 4 channels; for 3 and 4
+/// channels, the color channels are flipped together based on their median, and the alpha
+/// channel independently.
+pub fn distance_sign_correction<const N: usize>(
+    sdf: BitmapMut<'_, f32, N>,
+    shape: &Shape,
+    projection: &Projection,
+    sdf_zero_value: f32,
+    fill_rule: FillRule,
+) {
+    match N {
+        1 => single_distance_sign_correction(sdf, shape, projection, sdf_zero_value, fill_rule),
+        3 | 4 => multi_distance_sign_correction(sdf, shape, projection, sdf_zero_value, fill_rule),
+        _ => panic!("distance sign correction requires 1, 3 or 4 channels"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::tests::add_rectangle;
+    use crate::Bitmap;
+
+    const SIZE: usize = 8;
+
+    /// Two nested squares with the same orientation, so the inner one has a winding number of 2.
+    fn nested_squares() -> Shape {
+        let mut shape = Shape::default();
+        add_rectangle(&mut shape, 0.0, 0.0, 8.0, 8.0);
+        add_rectangle(&mut shape, 2.0, 2.0, 6.0, 6.0);
+        shape
+    }
+
+    fn is_inner(x: usize, y: usize) -> bool {
+        (2..6).contains(&x) && (2..6).contains(&y)
+    }
+
+    #[test]
+    fn single_channel_sign_correction() {
+        let shape = nested_squares();
+        for fill_rule in [FillRule::NonZero, FillRule::Odd] {
+            let mut sdf = Bitmap::<f32, 1>::new(SIZE, SIZE);
+            sdf.pixels_mut().fill(0.25);
+            distance_sign_correction(sdf.as_mut(), &shape, &Projection::default(), 0.5, fill_rule);
+            for y in 0..SIZE {
+                for x in 0..SIZE {
+                    let filled = fill_rule == FillRule::NonZero || !is_inner(x, y);
+                    let expected = if filled { 0.75 } else { 0.25 };
+                    assert_eq!(sdf.pixel(x, y)[0], expected, "{fill_rule:?} {x} {y}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn multi_channel_sign_correction() {
+        let shape = nested_squares();
+        for fill_rule in [FillRule::NonZero, FillRule::Odd] {
+            let mut sdf = Bitmap::<f32, 4>::new(SIZE, SIZE);
+            for pixel in sdf.pixels_mut().chunks_mut(4) {
+                pixel.copy_from_slice(&[0.25, 0.0, 0.75, 0.9]);
+            }
+            distance_sign_correction(sdf.as_mut(), &shape, &Projection::default(), 0.5, fill_rule);
+            for y in 0..SIZE {
+                for x in 0..SIZE {
+                    let filled = fill_rule == FillRule::NonZero || !is_inner(x, y);
+                    // The color channels are flipped by their median, and alpha by itself.
+                    let expected = if filled {
+                        [0.75, 1.0, 0.25, 0.9]
+                    } else {
+                        [0.25, 0.0, 0.75, 1.0 - 0.9]
+                    };
+                    assert_eq!(sdf.pixel(x, y), &expected, "{fill_rule:?} {x} {y}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rasterizes_with_fill_rule() {
+        let shape = nested_squares();
+        for fill_rule in [FillRule::NonZero, FillRule::Odd] {
+            let mut mask = Bitmap::<f32, 1>::new(SIZE, SIZE);
+            rasterize(mask.as_mut(), &shape, &Projection::default(), fill_rule);
+            for y in 0..SIZE {
+                for x in 0..SIZE {
+                    let filled = fill_rule == FillRule::NonZero || !is_inner(x, y);
+                    assert_eq!(mask.pixel(x, y)[0], filled as u8 as f32);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rasterizes_with_inverse_y_axis() {
+        // A strip along the bottom of the shape ends up in the last rows of the bitmap.
+        let mut shape = Shape::default();
+        add_rectangle(&mut shape, 0.0, 0.0, 8.0, 2.0);
+        shape.inverse_y_axis = true;
+        let mut mask = Bitmap::<f32, 1>::new(SIZE, SIZE);
+        rasterize(
+            mask.as_mut(),
+            &shape,
+            &Projection::default(),
+            FillRule::NonZero,
+        );
+        for y in 0..SIZE {
+            assert_eq!(mask.pixel(0, y)[0], (y >= SIZE - 2) as u8 as f32);
+        }
+    }
+
+    #[test]
+    fn rasterizes_coverage() {
+        let mut shape = Shape::default();
+        add_rectangle(&mut shape, 0.0, 0.0, 4.5, 4.5);
+        let mut mask = Bitmap::<f32, 1>::new(SIZE, SIZE);
+        let projection = Projection::default();
+        rasterize_supersampled(mask.as_mut(), &shape, &projection, FillRule::NonZero, 4);
+        assert_eq!(mask.pixel(0, 0)[0], 1.0);
+        assert_eq!(mask.pixel(4, 0)[0], 0.5);
+        assert_eq!(mask.pixel(0, 4)[0], 0.5);
+        assert_eq!(mask.pixel(4, 4)[0], 0.25);
+        assert_eq!(mask.pixel(5, 5)[0], 0.0);
+    }
+
+    fn render<const M: usize, const N: usize>(
+        sdf: &Bitmap<f32, N>,
+        sdf_px_range: Range,
+    ) -> Bitmap<f32, M> {
+        let mut output = Bitmap::new(sdf.width(), sdf.height());
+        render_sdf(output.as_mut(), sdf.as_ref(), sdf_px_range, 0.5);
+        output
+    }
+
+    #[test]
+    fn renders_sdf() {
+        let mut sdf = Bitmap::<f32, 1>::new(4, 1);
+        sdf.pixels_mut()
+            .copy_from_slice(&[0.625, 0.375, 0.875, 0.0]);
+        let output = render::<1, 1>(&sdf, Range::symmetrical(2.0));
+        assert_eq!(output.pixels(), [0.75, 0.25, 1.0, 0.0]);
+        let output = render::<1, 1>(&sdf, Range::new(0.0, 0.0));
+        assert_eq!(output.pixels(), [1.0, 0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn renders_msdf_median() {
+        let mut msdf = Bitmap::<f32, 3>::new(2, 1);
+        *msdf.pixel_mut(0, 0) = [0.25, 0.625, 0.875];
+        *msdf.pixel_mut(1, 0) = [0.875, 0.375, 0.0];
+        let output = render::<1, 3>(&msdf, Range::symmetrical(2.0));
+        assert_eq!(output.pixel(0, 0)[0], 0.75);
+        assert_eq!(output.pixel(1, 0)[0], 0.25);
+        let output = render::<3, 3>(&msdf, Range::symmetrical(2.0));
+        assert_eq!(*output.pixel(0, 0), [0.0, 0.75, 1.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot render")]
+    fn rejects_mismatched_channels() {
+        let msdf = Bitmap::<f32, 3>::new(2, 2);
+        render::<2, 3>(&msdf, Range::symmetrical(2.0));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::min;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum FillRule {
     NonZero,
     Odd,
@@ -8,6 +8,13 @@ pub enum FillRule {
     Negative,
 }
 
+impl Default for FillRule {
+    #[inline]
+    fn default() -> Self {
+        Self::NonZero
+    }
+}
+
 pub fn interpret_fill_rule(intersections: i32, fill_rule: FillRule) -> bool {
     match fill_rule {
         FillRule::NonZero => intersections != 0,
@@ -17,6 +24,7 @@ pub fn interpret_fill_rule(intersections: i32, fill_rule: FillRule) -> bool {
     }
 }
 
+#[derive(Copy, Clone, Debug)]
 pub struct Intersection {
     pub x: f64,
     pub direction: i32,
@@ -28,12 +36,23 @@ impl Intersection {
     }
 }
 
+#[derive(Clone, Default, Debug)]
 pub struct Scanline {
     intersections: Vec<Intersection>,
     last_index: usize,
 }
 
 impl Scanline {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_intersections(&mut self, intersections: Vec<Intersection>) {
+        self.intersections = intersections;
+        self.preprocess();
+    }
+
     pub fn clear(&mut self) {
         self.intersections.clear();
     }
@@ -136,7 +155,7 @@ impl Scanline {
                     return None;
                 }
                 index -= 1;
-                if x < self.intersections[index].x {
+                if x >= self.intersections[index].x {
                     break;
                 }
             }
@@ -169,3 +188,58 @@ impl Scanline {
         interpret_fill_rule(self.sum_intersections(x), fill_rule)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scanline() -> Scanline {
+        let mut scanline = Scanline::new();
+        scanline.set_intersections(vec![
+            Intersection::new(5.0, 1),
+            Intersection::new(1.0, 1),
+            Intersection::new(3.0, -1),
+        ]);
+        scanline
+    }
+
+    #[test]
+    fn moves_forward_and_back() {
+        let mut scanline = scanline();
+        assert_eq!(scanline.move_to(0.0), None);
+        assert_eq!(scanline.move_to(6.0), Some(2));
+        // Moving back has to stop at the last intersection at or before x.
+        assert_eq!(scanline.move_to(2.0), Some(0));
+        assert_eq!(scanline.move_to(3.0), Some(1));
+        assert_eq!(scanline.move_to(0.5), None);
+        assert_eq!(scanline.move_to(4.0), Some(1));
+    }
+
+    #[test]
+    fn sums_intersections() {
+        let mut scanline = scanline();
+        let samples = [
+            (0.0, 0, 0),
+            (2.0, 1, 1),
+            (4.0, 2, 0),
+            (6.0, 3, 1),
+            (2.0, 1, 1),
+        ];
+        for (x, count, sum) in samples {
+            assert_eq!(scanline.count_intersections(x), count);
+            assert_eq!(scanline.sum_intersections(x), sum);
+        }
+    }
+
+    #[test]
+    fn fill_rules() {
+        assert!(interpret_fill_rule(2, FillRule::NonZero));
+        assert!(!interpret_fill_rule(2, FillRule::Odd));
+        assert!(interpret_fill_rule(-1, FillRule::Odd));
+        assert!(!interpret_fill_rule(-1, FillRule::Positive));
+        assert!(interpret_fill_rule(-1, FillRule::Negative));
+        let mut scanline = scanline();
+        assert!(scanline.filled(2.0, FillRule::NonZero));
+        assert!(!scanline.filled(4.0, FillRule::NonZero));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::{
+    clamp_0_b, median, mix, BitmapRef, FillRule, Intersection, Projection, Scanline, Shape,
+};
+
+/// The result of [`estimate_sdf_error`].
+#[derive(Clone, Default, Debug)]
+pub struct SDFErrorEstimate {
+    /// The error of each interval between two adjacent pixel rows, i.e. `rows[y]` covers the
+    /// space between the centers of rows `y` and `y + 1`.
+    pub rows: Vec<f64>,
+    /// The average error of all rows.
+    pub total: f64,
+}
+
+#[inline]
+fn mix_f32(a: f32, b: f32, t: f64) -> f32 {
+    mix(a as f64, b as f64, t) as f32
+}
+
+#[inline]
+fn direction(lv: f32, rv: f32) -> i32 {
+    if rv > lv {
+        1
+    } else {
+        -1
+    }
+}
+
+fn scanline_single<const N: usize>(
+    sdf: BitmapRef<'_, f32, N>,
+    projection: &Projection,
+    [b, t]: [usize; 2],
+    bt: f64,
+) -> Vec<Intersection> {
+    let mut intersections = Vec::new();
+    let mut rv = mix_f32(sdf.pixel(0, b)[0], sdf.pixel(0, t)[0], bt);
+    if rv > 0.5 {
+        intersections.push(Intersection::new(-1e240, 1));
+    }
+    for r in 1..sdf.width() {
+        let l = r - 1;
+        let lv = rv;
+        rv = mix_f32(sdf.pixel(r, b)[0], sdf.pixel(r, t)[0], bt);
+        if lv != rv {
+            let lr = (0.5 - lv) as f64 / (rv - lv) as f64;
+            if (0.0..=1.0).contains(&lr) {
+                intersections.push(Intersection::new(
+                    projection.unproject_x(l as f64 + lr + 0.5),
+                    direction(lv, rv),
+                ));
+            }
+        }
+    }
+    intersections
+}
+
+fn scanline_multi<const N: usize>(
+    sdf: BitmapRef<'_, f32, N>,
+    projection: &Projection,
+    [b, t]: [usize; 2],
+    bt: f64,
+) -> Vec<Intersection> {
+    let sample = |x: usize| -> [f32; 3] {
+        let (bp, tp) = (sdf.pixel(x, b), sdf.pixel(x, t));
+        [
+            mix_f32(bp[0], tp[0], bt),
+            mix_f32(bp[1], tp[1], bt),
+            mix_f32(bp[2], tp[2], bt),
+        ]
+    };
+    let mut intersections = Vec::new();
+    let mut rv = sample(0);
+    let mut inside = median(rv[0], rv[1], rv[2]) > 0.5;
+    if inside {
+        intersections.push(Intersection::new(-1e240, 1));
+    }
+    let mut new_intersections = Vec::with_capacity(3);
+    for r in 1..sdf.width() {
+        let l = r - 1;
+        let lv = rv;
+        rv = sample(r);
+        new_intersections.clear();
+        for i in 0..3 {
+            if lv[i] != rv[i] {
+                let lr = (0.5 - lv[i]) as f64 / (rv[i] - lv[i]) as f64;
+                if (0.0..=1.0).contains(&lr) {
+                    let v = [
+                        mix_f32(lv[0], rv[0], lr),
+                        mix_f32(lv[1], rv[1], lr),
+                        mix_f32(lv[2], rv[2], lr),
+                    ];
+                    if median(v[0], v[1], v[2]) == v[i] {
+                        new_intersections.push(Intersection::new(
+                            projection.unproject_x(l as f64 + lr + 0.5),
+                            direction(lv[i], rv[i]),
+                        ));
+                    }
+                }
+            }
+        }
+        // Sort new intersections
+        new_intersections.sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap());
+        // Add new intersections and adjust inside state
+        for intersection in &new_intersections {
+            if (intersection.direction > 0) != inside {
+                intersections.push(*intersection);
+                inside = !inside;
+            }
+        }
+        // Consistency check
+        let rv_scalar = median(rv[0], rv[1], rv[2]);
+        if (rv_scalar > 0.5) != inside && rv_scalar != 0.5 && !intersections.is_empty() {
+            intersections.pop();
+            inside = !inside;
+        }
+    }
+    intersections
+}
+
+/// Computes the scanline of the shape as it would be reconstructed from the distance field, at
+/// the vertical position `y` in shape coordinates. Supports 1, 3 and 4-channel fields, where
+/// the multi-channel ones are evaluated by their median.
+pub fn scanline_sdf<const N: usize>(
+    line: &mut Scanline,
+    sdf: BitmapRef<'_, f32, N>,
+    projection: &Projection,
+    y: f64,
+    inverse_y_axis: bool,
+) {
+    assert!(
+        matches!(N, 1 | 3 | 4),
+        "scanline evaluation requires 1, 3 or 4 channels"
+    );
+    let (w, h) = (sdf.width(), sdf.height());
+    if w == 0 || h == 0 {
+        line.set_intersections(Vec::new());
+        return;
+    }
+    let mut pixel_y = clamp_0_b(projection.project_y(y) - 0.5, (h - 1) as f64);
+    if inverse_y_axis {
+        pixel_y = (h - 1) as f64 - pixel_y;
+    }
+    let b = (pixel_y.floor() as usize).min(h - 1);
+    let mut t = b + 1;
+    let mut bt = pixel_y - b as f64;
+    if t >= h {
+        t = h - 1;
+        bt = 1.0;
+    }
+    let intersections = if N == 1 {
+        scanline_single(sdf, projection, [b, t], bt)
+    } else {
+        scanline_multi(sdf, projection, [b, t], bt)
+    };
+    line.set_intersections(intersections);
+}
+
+/// Estimates the portion of the area that will be filled incorrectly when rendering the
+/// distance field, by comparing `scanlines_per_row` scanlines between each pair of pixel rows
+/// against the exact scanlines of the shape.
+pub fn estimate_sdf_error<const N: usize>(
+    sdf: BitmapRef<'_, f32, N>,
+    shape: &Shape,
+    projection: &Projection,
+    scanlines_per_row: usize,
+    fill_rule: FillRule,
+) -> SDFErrorEstimate {
+    let (w, h) = (sdf.width(), sdf.height());
+    if w <= 1 || h <= 1 || scanlines_per_row < 1 {
+        return SDFErrorEstimate::default();
+    }
+    let sub_row_size = 1.0 / scanlines_per_row as f64;
+    let x_from = projection.unproject_x(0.5);
+    let x_to = projection.unproject_x(w as f64 - 0.5);
+    let overlap_factor = 1.0 / (x_to - x_from);
+    let mut ref_scanline = Scanline::new();
+    let mut sdf_scanline = Scanline::new();
+    let rows: Vec<f64> = (0..h - 1)
+        .map(|row| {
+            let mut error = 0.0;
+            for sub_row in 0..scanlines_per_row {
+                let bt = (sub_row as f64 + 0.5) * sub_row_size;
+                let y = projection.unproject_y(row as f64 + bt + 0.5);
+                shape.scanline(&mut ref_scanline, y);
+                scanline_sdf(&mut sdf_scanline, sdf, projection, y, shape.inverse_y_axis);
+                error += 1.0
+                    - overlap_factor
+                        * Scanline::overlap(&ref_scanline, &sdf_scanline, x_from, x_to, fill_rule);
+            }
+            error / scanlines_per_row as f64
+        })
+        .collect();
+    let total = rows.iter().sum::<f64>() / rows.len() as f64;
+    SDFErrorEstimate { rows, total }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::tests::add_rectangle;
+    use crate::Bitmap;
+
+    /// A vertical strip between x = 2 and x = 6 that spans the whole bitmap.
+    fn strip() -> Shape {
+        let mut shape = Shape::default();
+        add_rectangle(&mut shape, 2.0, -1.0, 6.0, 9.0);
+        shape
+    }
+
+    /// The exact distance field of the strip with a range of 8, offset by `bias`.
+    fn strip_sdf<const N: usize>(bias: f32) -> Bitmap<f32, N> {
+        let mut sdf = Bitmap::new(8, 4);
+        for y in 0..4 {
+            for x in 0..8 {
+                let px = x as f32 + 0.5;
+                let distance = (px - 2.0).min(6.0 - px);
+                *sdf.pixel_mut(x, y) = [0.5 + distance / 8.0 + bias; N];
+            }
+        }
+        sdf
+    }
+
+    fn estimate_error<const N: usize>(sdf: &Bitmap<f32, N>) -> SDFErrorEstimate {
+        let projection = Projection::default();
+        estimate_sdf_error(sdf.as_ref(), &strip(), &projection, 4, FillRule::NonZero)
+    }
+
+    #[test]
+    fn scanlines_sdf() {
+        let sdf = strip_sdf::<3>(0.0);
+        let mut line = Scanline::new();
+        scanline_sdf(&mut line, sdf.as_ref(), &Projection::default(), 2.0, false);
+        assert!(!line.filled(1.9, FillRule::NonZero));
+        assert!(line.filled(2.1, FillRule::NonZero));
+        assert!(line.filled(5.9, FillRule::NonZero));
+        assert!(!line.filled(6.1, FillRule::NonZero));
+    }
+
+    #[test]
+    fn exact_sdf_has_no_error() {
+        let estimate = estimate_error(&strip_sdf::<1>(0.0));
+        assert_eq!(estimate.rows.len(), 3);
+        assert!(estimate.total.abs() < 1e-9);
+        let estimate = estimate_error(&strip_sdf::<3>(0.0));
+        assert!(estimate.total.abs() < 1e-9);
+    }
+
+    #[test]
+    fn biased_sdf_has_error() {
+        // Moving each edge outwards by half a pixel fills 1 out of the 7 pixels scanned per row.
+        for estimate in [
+            estimate_error(&strip_sdf::<1>(1.0 / 16.0)),
+            estimate_error(&strip_sdf::<4>(1.0 / 16.0)),
+        ] {
+            for error in estimate.rows {
+                assert!((error - 1.0 / 7.0).abs() < 1e-6);
+            }
+            assert!((estimate.total - 1.0 / 7.0).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{min, DistanceMapping, Projection, Range};
+
+/// Everything a generator needs to know to place a shape in a bitmap: the projection from shape
+/// coordinates to pixels and the mapping from distances to stored values.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct SDFTransformation {
+    pub projection: Projection,
+    pub distance_mapping: DistanceMapping,
+}
+
+impl SDFTransformation {
+    #[inline]
+    pub const fn new(projection: Projection, distance_mapping: DistanceMapping) -> Self {
+        Self {
+            projection,
+            distance_mapping,
+        }
+    }
+
+    /// Uses a distance range measured in shape units.
+    #[inline]
+    pub fn with_range(projection: Projection, range: Range) -> Self {
+        Self::new(projection, DistanceMapping::new(range))
+    }
+
+    /// Uses a distance range measured in output pixels.
+    #[inline]
+    pub fn with_pixel_range(projection: Projection, px_range: Range) -> Self {
+        let scale = min(projection.scale.x, projection.scale.y);
+        Self::with_range(projection, px_range / scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Vector2;
+
+    #[test]
+    fn converts_pixel_range_with_smaller_scale() {
+        let projection = Projection::new(Vector2::new(4.0, 2.0), Vector2::new(1.0, 0.0));
+        let transformation =
+            SDFTransformation::with_pixel_range(projection, Range::symmetrical(4.0));
+        assert_eq!(
+            transformation,
+            SDFTransformation::with_range(projection, Range::new(-1.0, 1.0))
+        );
+        assert_eq!(transformation.distance_mapping.map(0.0), 0.5);
+        assert_eq!(transformation.distance_mapping.map(1.0), 1.0);
+    }
+}
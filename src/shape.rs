@@ -19,8 +19,8 @@ impl Bounds {
 
 #[derive(Default)]
 pub struct Shape {
-    contours: Vec<Contour>,
-    inverse_y_axis: bool,
+    pub(crate) contours: Vec<Contour>,
+    pub(crate) inverse_y_axis: bool,
 }
 
 impl Shape {
@@ -241,3 +241,35 @@ fn deconverge_edge(edge: &mut EdgeHolder, param: i32) {
     };
     edge.set_segment(new_segment);
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{EdgeColor, Vector2};
+
+    /// Adds a contour of white linear edges that joins the points in order.
+    pub(crate) fn add_polygon<'a>(shape: &'a mut Shape, points: &[(f64, f64)]) -> &'a mut Contour {
+        let contour = shape.add_empty_contour();
+        for (i, &(x, y)) in points.iter().enumerate() {
+            let (nx, ny) = points[(i + 1) % points.len()];
+            contour.add_edge(EdgeHolder::linear(
+                Vector2::new(x, y),
+                Vector2::new(nx, ny),
+                EdgeColor::White,
+            ));
+        }
+        contour
+    }
+
+    /// Adds a rectangle that starts at its bottom-left corner and goes up its left edge.
+    pub(crate) fn add_rectangle(shape: &mut Shape, l: f64, b: f64, r: f64, t: f64) -> &mut Contour {
+        add_polygon(shape, &[(l, b), (l, t), (r, t), (r, b)])
+    }
+
+    /// A square from the origin to `(size, size)`.
+    pub(crate) fn square(size: f64) -> Shape {
+        let mut shape = Shape::default();
+        add_rectangle(&mut shape, 0.0, 0.0, size, size);
+        shape
+    }
+}
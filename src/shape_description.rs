@@ -0,0 +1,497 @@
+use crate::{
+    Contour, CubicSegment, EdgeColor, EdgeHolder, LinearSegment, QuadraticSegment, Segment, Shape,
+    Vector2,
+};
+use std::fmt::{self, Write};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ParseErrorKind {
+    /// A character that is not valid at this point of the description.
+    UnexpectedCharacter(char),
+    /// The description ended in the middle of a contour.
+    UnexpectedEnd,
+    /// A coordinate could not be parsed as a number.
+    InvalidNumber,
+    /// An `@` directive other than `@invert-y`.
+    UnknownDirective,
+    /// A contour whose last point is neither `#` nor equal to its first point.
+    UnclosedContour,
+}
+
+/// An error encountered by [`Shape::from_description`]. Lines and columns start at 1.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        match &self.kind {
+            ParseErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character {c:?}"),
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ParseErrorKind::UnknownDirective => write!(f, "unknown directive"),
+            ParseErrorKind::UnclosedContour => write!(f, "contour is not closed"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            pos: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            kind,
+        }
+    }
+
+    /// Reports the character at the current position as unexpected.
+    fn unexpected(&self) -> ParseError {
+        match self.peek_raw() {
+            Some(c) => self.error(ParseErrorKind::UnexpectedCharacter(c)),
+            None => self.error(ParseErrorKind::UnexpectedEnd),
+        }
+    }
+
+    #[inline]
+    fn peek_raw(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek_raw()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    /// Skips whitespace and returns the next character without consuming it.
+    fn peek(&mut self) -> Option<char> {
+        while self.peek_raw().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+        self.peek_raw()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, ParseError> {
+        if !self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, '.' | '+' | '-'))
+        {
+            return Err(self.unexpected());
+        }
+        let (line, column, start) = (self.line, self.column, self.pos);
+        while self
+            .peek_raw()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-'))
+        {
+            // Signs may only appear at the start or right after an exponent.
+            let c = self.peek_raw().unwrap();
+            if matches!(c, '+' | '-')
+                && self.pos != start
+                && !self.input[..self.pos].ends_with(['e', 'E'])
+            {
+                break;
+            }
+            self.bump();
+        }
+        self.input[start..self.pos].parse().map_err(|_| ParseError {
+            line,
+            column,
+            kind: ParseErrorKind::InvalidNumber,
+        })
+    }
+
+    fn point(&mut self) -> Result<Vector2, ParseError> {
+        let x = self.number()?;
+        self.expect(',')?;
+        let y = self.number()?;
+        Ok(Vector2::new(x, y))
+    }
+
+    fn color(&mut self) -> Option<EdgeColor> {
+        let color = match self.peek()? {
+            'c' | 'C' => EdgeColor::Cyan,
+            'm' | 'M' => EdgeColor::Magenta,
+            'y' | 'Y' => EdgeColor::Yellow,
+            'w' | 'W' => EdgeColor::White,
+            _ => return None,
+        };
+        self.bump();
+        Some(color)
+    }
+
+    fn directive(&mut self, shape: &mut Shape) -> Result<(), ParseError> {
+        let (line, column) = (self.line, self.column);
+        self.bump();
+        let start = self.pos;
+        while self
+            .peek_raw()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            self.bump();
+        }
+        match &self.input[start..self.pos] {
+            "invert-y" => {
+                shape.inverse_y_axis = true;
+                Ok(())
+            }
+            _ => Err(ParseError {
+                line,
+                column,
+                kind: ParseErrorKind::UnknownDirective,
+            }),
+        }
+    }
+
+    fn contour(&mut self) -> Result<Contour, ParseError> {
+        let mut contour = Contour::default();
+        self.expect('{')?;
+        if self.eat('}') {
+            return Ok(contour);
+        }
+        let first = self.point()?;
+        let mut prev = first;
+        loop {
+            if self.peek() == Some('}') {
+                if prev != first {
+                    return Err(self.error(ParseErrorKind::UnclosedContour));
+                }
+                break;
+            }
+            self.expect(';')?;
+            // An optional color and control points describe the edge up to the next point.
+            let color = self.color();
+            let mut control_points = Vec::with_capacity(2);
+            if self.eat('(') {
+                control_points.push(self.point()?);
+                if self.eat(';') {
+                    control_points.push(self.point()?);
+                }
+                self.expect(')')?;
+            }
+            if color.is_some() || !control_points.is_empty() {
+                self.expect(';')?;
+            }
+            let closing = self.eat('#');
+            let point = if closing { first } else { self.point()? };
+            let color = color.unwrap_or(EdgeColor::White);
+            contour.add_edge(match control_points[..] {
+                [] => EdgeHolder::linear(prev, point, color),
+                [p1] => EdgeHolder::quadratic(prev, p1, point, color),
+                [p1, p2] => EdgeHolder::cubic(prev, p1, p2, point, color),
+                _ => unreachable!(),
+            });
+            prev = point;
+            if closing {
+                self.eat(';');
+                break;
+            }
+        }
+        self.expect('}')?;
+        Ok(contour)
+    }
+
+    fn shape(&mut self) -> Result<Shape, ParseError> {
+        let mut shape = Shape::default();
+        loop {
+            match self.peek() {
+                None => return Ok(shape),
+                Some('@') => self.directive(&mut shape)?,
+                Some('{') => shape.add_contour(self.contour()?),
+                Some(_) => return Err(self.unexpected()),
+            }
+        }
+    }
+}
+
+impl Shape {
+    /// Parses a shape from msdfgen's shape description format, e.g.
+    /// `{ 0,0; 0,1; y(1,2); 2,0; # }`. Each contour is enclosed in braces and lists its points
+    /// separated by semicolons, with `#` standing for the first point. The edge between two
+    /// points may be given a color (`c`, `m`, `y` or `w`) and one or two control points in
+    /// parentheses. Edges without a color are white. A leading `@invert-y` sets
+    /// `inverse_y_axis`.
+    pub fn from_description(description: &str) -> Result<Shape, ParseError> {
+        Parser::new(description).shape()
+    }
+
+    /// Writes the shape in the format read by [`Shape::from_description`]. Coordinates are
+    /// written so that they parse back to exactly the same values. Edge colors are included
+    /// unless all edges are white. Returns `None` if the shape is not valid, has non-finite
+    /// coordinates, or has edge colors other than cyan, magenta, yellow and white.
+    pub fn to_description(&self) -> Option<String> {
+        if !self.validate() {
+            return None;
+        }
+        let write_colors = self
+            .contours
+            .iter()
+            .flat_map(|contour| &contour.edges)
+            .any(|edge| edge.color != EdgeColor::White);
+        let mut output = String::new();
+        if self.inverse_y_axis {
+            output.push_str("@invert-y\n");
+        }
+        for contour in &self.contours {
+            output.push_str("{\n");
+            if !contour.edges.is_empty() {
+                for edge in &contour.edges {
+                    let color_code = if write_colors {
+                        match edge.color {
+                            EdgeColor::Yellow => "y",
+                            EdgeColor::Magenta => "m",
+                            EdgeColor::Cyan => "c",
+                            EdgeColor::White => "w",
+                            _ => return None,
+                        }
+                    } else {
+                        ""
+                    };
+                    match &edge.segment {
+                        Segment::Linear(LinearSegment(p0, _)) => {
+                            writeln!(output, "\t{};", coord(*p0)?).unwrap();
+                            if write_colors {
+                                writeln!(output, "\t\t{color_code};").unwrap();
+                            }
+                        }
+                        Segment::Quadratic(QuadraticSegment(p0, p1, _)) => {
+                            writeln!(output, "\t{};", coord(*p0)?).unwrap();
+                            writeln!(output, "\t\t{color_code}({});", coord(*p1)?).unwrap();
+                        }
+                        Segment::Cubic(CubicSegment(p0, p1, p2, _)) => {
+                            writeln!(output, "\t{};", coord(*p0)?).unwrap();
+                            writeln!(
+                                output,
+                                "\t\t{color_code}({}; {});",
+                                coord(*p1)?,
+                                coord(*p2)?
+                            )
+                            .unwrap();
+                        }
+                    }
+                }
+                output.push_str("\t#\n");
+            }
+            output.push_str("}\n");
+        }
+        Some(output)
+    }
+}
+
+/// Formats a number using the shortest representation that parses back to the same value.
+fn number(x: f64) -> String {
+    let (plain, exponent) = (format!("{x}"), format!("{x:e}"));
+    if exponent.len() < plain.len() {
+        exponent
+    } else {
+        plain
+    }
+}
+
+fn coord(p: Vector2) -> Option<String> {
+    if p.x.is_finite() && p.y.is_finite() {
+        Some(format!("{}, {}", number(p.x), number(p.y)))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(description: &str) -> ParseError {
+        Shape::from_description(description).err().unwrap()
+    }
+
+    fn error(description: &str) -> (usize, usize, ParseErrorKind) {
+        let error = parse_error(description);
+        (error.line, error.column, error.kind)
+    }
+
+    #[test]
+    fn parses_linear_contours() {
+        let shape =
+            Shape::from_description("{ 0,0; 0,1; 1,1; # }\n{ 2,2; 3,2; 2,3; 2,2 }").unwrap();
+        assert!(!shape.inverse_y_axis);
+        assert_eq!(shape.contours.len(), 2);
+        let edges = &shape.contours[0].edges;
+        assert_eq!(edges.len(), 3);
+        assert!(edges.iter().all(|edge| edge.color == EdgeColor::White));
+        assert!(matches!(
+            edges[0].segment,
+            Segment::Linear(LinearSegment(p0, p1))
+                if p0 == Vector2::new(0.0, 0.0) && p1 == Vector2::new(0.0, 1.0)
+        ));
+        assert!(matches!(
+            edges[2].segment,
+            Segment::Linear(LinearSegment(p0, p1))
+                if p0 == Vector2::new(1.0, 1.0) && p1 == Vector2::new(0.0, 0.0)
+        ));
+        assert_eq!(shape.contours[1].edges.len(), 3);
+    }
+
+    #[test]
+    fn parses_colors_and_control_points() {
+        let shape =
+            Shape::from_description("@invert-y\n{ 0,0; y(1,2); 2,0; M(3,1; 1e0,-2.5E-1); # }")
+                .unwrap();
+        assert!(shape.inverse_y_axis);
+        let edges = &shape.contours[0].edges;
+        assert_eq!(edges.len(), 2);
+        assert_eq!(edges[0].color, EdgeColor::Yellow);
+        assert!(matches!(
+            edges[0].segment,
+            Segment::Quadratic(QuadraticSegment(_, p1, p2))
+                if p1 == Vector2::new(1.0, 2.0) && p2 == Vector2::new(2.0, 0.0)
+        ));
+        assert_eq!(edges[1].color, EdgeColor::Magenta);
+        assert!(matches!(
+            edges[1].segment,
+            Segment::Cubic(CubicSegment(_, p1, p2, p3))
+                if p1 == Vector2::new(3.0, 1.0)
+                    && p2 == Vector2::new(1.0, -0.25)
+                    && p3 == Vector2::new(0.0, 0.0)
+        ));
+    }
+
+    #[test]
+    fn parses_empty_contours() {
+        let shape = Shape::from_description("{}{ }").unwrap();
+        assert_eq!(shape.contours.len(), 2);
+        assert!(shape
+            .contours
+            .iter()
+            .all(|contour| contour.edges.is_empty()));
+        assert!(Shape::from_description("").unwrap().contours.is_empty());
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        use ParseErrorKind::*;
+        assert_eq!(error("{ 0,0; 1,1 }"), (1, 12, UnclosedContour));
+        assert_eq!(
+            error("{\n  0,0;\n  1,x\n}"),
+            (3, 5, UnexpectedCharacter('x'))
+        );
+        assert_eq!(error("\n  @flip-y {}"), (2, 3, UnknownDirective));
+        assert_eq!(error("{ 0,0; 1.2.3,0; # }"), (1, 8, InvalidNumber));
+        assert_eq!(error("{ 0,0; 1,1;"), (1, 12, UnexpectedEnd));
+        assert_eq!(
+            error("{ 0,0; 1,1; # } x"),
+            (1, 17, UnexpectedCharacter('x'))
+        );
+        assert_eq!(
+            parse_error("{\n  0,0;\n  1,x\n}").to_string(),
+            "3:5: unexpected character 'x'"
+        );
+    }
+
+    /// The colors and exact coordinate bits of every edge, to also tell 0.0 and -0.0 apart.
+    fn edges(shape: &Shape) -> Vec<Vec<(EdgeColor, Vec<[u64; 2]>)>> {
+        let bits = |p: &Vector2| [p.x.to_bits(), p.y.to_bits()];
+        shape
+            .contours
+            .iter()
+            .map(|contour| {
+                contour
+                    .edges
+                    .iter()
+                    .map(|edge| {
+                        let points = match &edge.segment {
+                            Segment::Linear(LinearSegment(p0, p1)) => vec![bits(p0), bits(p1)],
+                            Segment::Quadratic(QuadraticSegment(p0, p1, p2)) => {
+                                vec![bits(p0), bits(p1), bits(p2)]
+                            }
+                            Segment::Cubic(CubicSegment(p0, p1, p2, p3)) => {
+                                vec![bits(p0), bits(p1), bits(p2), bits(p3)]
+                            }
+                        };
+                        (edge.color, points)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn assert_round_trip(description: &str) {
+        let shape = Shape::from_description(description).unwrap();
+        let output = shape.to_description().unwrap();
+        let parsed = Shape::from_description(&output).unwrap();
+        assert_eq!(parsed.inverse_y_axis, shape.inverse_y_axis);
+        assert_eq!(edges(&parsed), edges(&shape));
+        assert_eq!(parsed.to_description().unwrap(), output);
+    }
+
+    #[test]
+    fn round_trips_uncolored_shape() {
+        assert_round_trip("{ 0,0; 0,1; (1,2); 2,1; (3,1; 1,-1); # }\n{ 4,4; 5,4; 4,5; # }");
+    }
+
+    #[test]
+    fn round_trips_colored_shape() {
+        assert_round_trip("{ 0,0; c; 0,1; m(1,2); 2,1; y(3,1; 1,-1); # }\n{ 4,4; w; 5,4; y; # }");
+    }
+
+    #[test]
+    fn round_trips_special_coordinates() {
+        assert_round_trip("@invert-y { -0,0; 1e-20,-0; 0.1,3e300; 1.7976931348623157e308,0.3; # }");
+        let shape = Shape::from_description("{ -0,0; 1e-20,-0; 0.1,1; # }").unwrap();
+        let output = shape.to_description().unwrap();
+        assert!(output.contains("-0, 0;"));
+        assert!(output.contains("1e-20, -0;"));
+    }
+
+    #[test]
+    fn writes_description() {
+        let shape = Shape::from_description("@invert-y { 0,0; y(1,2); 2,0; m; # }").unwrap();
+        assert_eq!(
+            shape.to_description().unwrap(),
+            "@invert-y\n{\n\t0, 0;\n\t\ty(1, 2);\n\t2, 0;\n\t\tm;\n\t#\n}\n"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{ContourCombiner, EdgeSegment, EdgeSelector, Shape, Vector2};
+use std::ops::Range;
+
+type DistanceOf<C> = <<C as ContourCombiner>::Selector as EdgeSelector>::Distance;
+type CacheOf<C> = <<C as ContourCombiner>::Selector as EdgeSelector>::Cache;
+
+/// Finds the distance between a point and a shape. The edges of the shape are copied into a
+/// single list so that edge selectors can refer to any edge of the shape by its index.
+pub struct ShapeDistanceFinder<C: ContourCombiner> {
+    edges: Vec<EdgeSegment>,
+    contours: Vec<Range<usize>>,
+    contour_combiner: C,
+    shape_edge_cache: Vec<CacheOf<C>>,
+}
+
+impl<C: ContourCombiner> ShapeDistanceFinder<C> {
+    pub fn new(shape: &Shape) -> Self {
+        let mut edges = Vec::with_capacity(shape.edge_count());
+        let mut contours = Vec::with_capacity(shape.contours.len());
+        for contour in &shape.contours {
+            let start = edges.len();
+            edges.extend(contour.edges.iter().map(|edge| (**edge).clone()));
+            contours.push(start..edges.len());
+        }
+        let mut shape_edge_cache = Vec::with_capacity(edges.len());
+        shape_edge_cache.resize_with(edges.len(), CacheOf::<C>::default);
+        Self {
+            edges,
+            contours,
+            contour_combiner: C::new(shape),
+            shape_edge_cache,
+        }
+    }
+
+    pub fn distance(&mut self, origin: Vector2) -> DistanceOf<C> {
+        self.contour_combiner.reset(origin);
+        for (contour_index, contour) in self.contours.iter().enumerate() {
+            if contour.is_empty() {
+                continue;
+            }
+            let edge_selector = self.contour_combiner.edge_selector(contour_index);
+            let mut prev_edge = if contour.len() >= 2 {
+                contour.end - 2
+            } else {
+                contour.start
+            };
+            let mut cur_edge = contour.end - 1;
+            for next_edge in contour.clone() {
+                edge_selector.add_edge(
+                    &mut self.shape_edge_cache[cur_edge],
+                    &self.edges,
+                    prev_edge,
+                    cur_edge,
+                    next_edge,
+                );
+                prev_edge = cur_edge;
+                cur_edge = next_edge;
+            }
+        }
+        self.contour_combiner.distance(&self.edges)
+    }
+
+    pub fn one_shot_distance(shape: &Shape, origin: Vector2) -> DistanceOf<C> {
+        Self::new(shape).distance(origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::tests::{add_rectangle, square};
+    use crate::{
+        DistanceType, OverlappingContourCombiner, PseudoDistanceSelector, SimpleContourCombiner,
+        TrueDistanceSelector,
+    };
+
+    /// Two unit squares that overlap between x = 0.5 and x = 1.
+    fn overlapping_squares() -> Shape {
+        let mut shape = square(1.0);
+        add_rectangle(&mut shape, 0.5, 0.0, 1.5, 1.0);
+        shape
+    }
+
+    fn distance<C: ContourCombiner>(shape: &Shape, x: f64, y: f64) -> f64 {
+        ShapeDistanceFinder::<C>::one_shot_distance(shape, Vector2::new(x, y)).resolve()
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{a} != {b}");
+    }
+
+    #[test]
+    fn true_distance_of_square() {
+        type Simple = SimpleContourCombiner<TrueDistanceSelector>;
+        type Overlapping = OverlappingContourCombiner<TrueDistanceSelector>;
+        let shape = square(1.0);
+        for distance in [distance::<Simple>, distance::<Overlapping>] {
+            assert_close(distance(&shape, 0.5, 0.5), 0.5);
+            assert_close(distance(&shape, 0.25, 0.5), 0.25);
+            assert_close(distance(&shape, 2.0, 0.5), -1.0);
+            assert_close(distance(&shape, 2.0, 2.0), -2.0f64.sqrt());
+        }
+    }
+
+    #[test]
+    fn pseudo_distance_of_square() {
+        type Simple = SimpleContourCombiner<PseudoDistanceSelector>;
+        type Overlapping = OverlappingContourCombiner<PseudoDistanceSelector>;
+        let shape = square(1.0);
+        for distance in [distance::<Simple>, distance::<Overlapping>] {
+            assert_close(distance(&shape, 0.5, 0.5), 0.5);
+            assert_close(distance(&shape, 0.25, 0.5), 0.25);
+            assert_close(distance(&shape, 2.0, 0.5), -1.0);
+            // Past the corner, the distance is to the extension of the nearest edge.
+            assert_close(distance(&shape, 2.0, 2.0), -1.0);
+        }
+    }
+
+    #[test]
+    fn overlapping_contours() {
+        let shape = overlapping_squares();
+        // The simple combiner picks the nearest edge, even though it is inside the other square.
+        let simple = distance::<SimpleContourCombiner<TrueDistanceSelector>>(&shape, 0.9, 0.5);
+        assert_close(simple, 0.1);
+        // The overlapping combiner picks the contour that the point is deepest inside.
+        let overlapping =
+            distance::<OverlappingContourCombiner<TrueDistanceSelector>>(&shape, 0.9, 0.5);
+        assert_close(overlapping, 0.4);
+        // Outside of both squares, the combiners agree.
+        for distance in [
+            distance::<SimpleContourCombiner<TrueDistanceSelector>>,
+            distance::<OverlappingContourCombiner<TrueDistanceSelector>>,
+        ] {
+            assert_close(distance(&shape, 2.0, 0.5), -0.5);
+            assert_close(distance(&shape, -0.5, 0.5), -0.5);
+        }
+    }
+
+    #[test]
+    fn reuses_finder() {
+        let shape = square(1.0);
+        let mut finder =
+            ShapeDistanceFinder::<SimpleContourCombiner<TrueDistanceSelector>>::new(&shape);
+        assert_close(finder.distance(Vector2::new(0.5, 0.5)), 0.5);
+        assert_close(finder.distance(Vector2::new(2.0, 0.5)), -1.0);
+        assert_close(finder.distance(Vector2::new(0.5, 0.5)), 0.5);
+    }
+}
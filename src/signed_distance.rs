@@ -18,11 +18,18 @@ impl SignedDistance {
     }
 }
 
+impl Default for SignedDistance {
+    #[inline]
+    fn default() -> Self {
+        Self::infinite()
+    }
+}
+
 impl PartialOrd for SignedDistance {
     #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         let a_dist = self.distance.abs();
-        let b_dist = self.distance.abs();
+        let b_dist = other.distance.abs();
         if a_dist < b_dist {
             Some(Ordering::Less)
         } else if a_dist > b_dist {
@@ -32,3 +39,18 @@ impl PartialOrd for SignedDistance {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_by_absolute_distance_then_dot() {
+        let near = SignedDistance::new(-1.0, 0.5);
+        let far = SignedDistance::new(2.0, 0.0);
+        assert!(near < far);
+        assert!(far > near);
+        assert!(SignedDistance::new(1.0, 0.2) < SignedDistance::new(-1.0, 0.8));
+        assert!(far < SignedDistance::infinite());
+    }
+}